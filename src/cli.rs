@@ -0,0 +1,293 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use structopt::StructOpt;
+
+use raytracing::film::filter::FilterKind;
+use raytracing::output::OutputFormat;
+use raytracing::render::TileOrder;
+use raytracing::sampler::SamplerKind;
+use raytracing::scene::ImageSettings;
+use raytracing::RenderSettings;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BuiltinScene {
+    Random,
+    ThreeSpheres,
+    CornellBox,
+    CornellSmoke,
+}
+
+impl FromStr for BuiltinScene {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(BuiltinScene::Random),
+            "three-spheres" => Ok(BuiltinScene::ThreeSpheres),
+            "cornell-box" => Ok(BuiltinScene::CornellBox),
+            "cornell-smoke" => Ok(BuiltinScene::CornellSmoke),
+            _ => Err(format!("unknown scene '{}'", s)),
+        }
+    }
+}
+
+// Seconds, or minutes and hours with an m or h suffix
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = match s.char_indices().last() {
+        Some((i, 's')) => (&s[..i], 1.0),
+        Some((i, 'm')) => (&s[..i], 60.0),
+        Some((i, 'h')) => (&s[..i], 3600.0),
+        _ => (s, 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(Duration::from_secs_f64(value * unit)),
+        _ => Err(format!("invalid duration '{}'", s)),
+    }
+}
+
+#[derive(StructOpt)]
+#[structopt(about = "Renders a scene file or a built-in scene")]
+pub struct Options {
+    /// Scene description file
+    #[structopt(parse(from_os_str))]
+    pub scene_file: Option<PathBuf>,
+
+    /// Built-in scene, used when no scene file is given
+    #[structopt(
+        long,
+        possible_values = &["random", "three-spheres", "cornell-box", "cornell-smoke"],
+        conflicts_with = "scene-file"
+    )]
+    pub scene: Option<BuiltinScene>,
+
+    /// Image width in pixels, the height follows the aspect ratio unless given
+    #[structopt(short, long)]
+    pub width: Option<usize>,
+
+    /// Image height in pixels
+    #[structopt(long)]
+    pub height: Option<usize>,
+
+    /// Image aspect ratio (width / height)
+    #[structopt(long, conflicts_with = "height")]
+    pub aspect_ratio: Option<f32>,
+
+    /// Samples per pixel
+    #[structopt(short, long)]
+    pub samples: Option<u32>,
+
+    /// Samples every pixel takes before adaptive sampling may stop
+    #[structopt(long)]
+    pub min_samples: Option<u32>,
+
+    /// Enables adaptive sampling: pixels stop being sampled once the
+    /// standard error of their luminance falls under this fraction of it
+    #[structopt(long)]
+    pub noise_threshold: Option<f32>,
+
+    /// Bounces every path takes before Russian roulette may end it
+    #[structopt(long)]
+    pub min_depth: Option<u32>,
+
+    /// Rays a path is made of at most, paths are only ended by Russian
+    /// roulette by default
+    #[structopt(long)]
+    pub max_depth: Option<u32>,
+
+    /// Sampler of the pixel positions, lens, time and scattering directions
+    #[structopt(long, possible_values = SamplerKind::NAMES)]
+    pub sampler: Option<SamplerKind>,
+
+    /// Pixel reconstruction filter
+    #[structopt(long, possible_values = FilterKind::NAMES)]
+    pub filter: Option<FilterKind>,
+
+    /// Radius of the reconstruction filter in pixels, each filter has its own
+    /// default
+    #[structopt(long)]
+    pub filter_radius: Option<f32>,
+
+    /// Side of the square tiles rendered by each thread, in pixels
+    #[structopt(long)]
+    pub tile_size: Option<usize>,
+
+    /// Order tiles are rendered in
+    #[structopt(long, possible_values = TileOrder::NAMES)]
+    pub tile_order: Option<TileOrder>,
+
+    /// Number of passes over the whole image the samples are spread over
+    #[structopt(long)]
+    pub passes: Option<u32>,
+
+    /// Keeps adding passes until this time, in seconds or followed by m or h,
+    /// has elapsed. Passes take one sample unless --passes is given. No pass
+    /// is started that is expected to end past the time limit, and the one
+    /// running at the limit is cut short
+    #[structopt(long, parse(try_from_str = parse_duration))]
+    pub time_limit: Option<Duration>,
+
+    /// Number of rendering threads, defaults to the number of CPUs
+    #[structopt(short = "j", long)]
+    pub threads: Option<usize>,
+
+    /// Output file, the image is written to stdout otherwise
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+
+    /// Output format, guessed from the output file extension by default.
+    /// pfm and hdr keep the unclamped radiance
+    #[structopt(short, long, possible_values = OutputFormat::NAMES)]
+    pub format: Option<OutputFormat>,
+
+    /// Writes the number of samples of every pixel as an image, from blue for
+    /// the fewest to red for the most
+    #[structopt(long, parse(from_os_str))]
+    pub heatmap: Option<PathBuf>,
+
+    /// Saves the render to this file after every pass and every checkpoint
+    /// interval, so that it can be resumed if interrupted
+    #[structopt(long, parse(from_os_str))]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Time between checkpoints within a pass, in seconds or followed by m
+    /// or h. Defaults to a minute
+    #[structopt(long, parse(try_from_str = parse_duration), requires = "checkpoint")]
+    pub checkpoint_interval: Option<Duration>,
+
+    /// Carries on with the render saved in a checkpoint, with the same scene
+    /// and settings. More samples per pixel than the checkpoint was rendered
+    /// with are added to it
+    #[structopt(long, parse(from_os_str))]
+    pub resume: Option<PathBuf>,
+
+    /// Seed for the procedural scene generation and the sampling, renders
+    /// with the same seed and settings are identical
+    #[structopt(long)]
+    pub seed: Option<u64>,
+
+    /// Do not display progress bars
+    #[structopt(short, long)]
+    pub quiet: bool,
+
+    /// Test every object for every ray instead of using a BVH, for debugging
+    #[structopt(long)]
+    pub linear: bool,
+}
+
+impl Options {
+    // Overrides the scene image settings with the command line ones
+    pub fn apply(&self, image: &mut ImageSettings) -> Result<(), String> {
+        let aspect_ratio = self.aspect_ratio.unwrap_or_else(|| image.aspect_ratio());
+        if aspect_ratio.is_nan() || aspect_ratio <= 0.0 {
+            return Err("the aspect ratio must be positive".to_string());
+        }
+
+        if let Some(width) = self.width {
+            image.width = width;
+        }
+        if let Some(height) = self.height {
+            image.height = height;
+        } else if self.width.is_some() || self.aspect_ratio.is_some() {
+            image.height = (image.width as f32 / aspect_ratio) as usize;
+        }
+        if image.width < 2 || image.height < 2 {
+            return Err(format!(
+                "image size {}x{} is too small",
+                image.width, image.height
+            ));
+        }
+
+        if let Some(samples) = self.samples {
+            if samples == 0 {
+                return Err("at least one sample per pixel is required".to_string());
+            }
+            image.samples_per_pixel = samples;
+        }
+        if let Some(min_samples) = self.min_samples {
+            image.min_samples = min_samples;
+        }
+        if let Some(threshold) = self.noise_threshold {
+            if threshold.is_nan() || threshold <= 0.0 {
+                return Err("the noise threshold must be positive".to_string());
+            }
+            image.noise_threshold = Some(threshold);
+        }
+        if let Some(min_depth) = self.min_depth {
+            image.min_depth = min_depth;
+        }
+        if let Some(max_depth) = self.max_depth {
+            if max_depth == 0 {
+                return Err("the maximum depth must be positive".to_string());
+            }
+            image.max_depth = Some(max_depth);
+        }
+        if let Some(sampler) = self.sampler {
+            image.sampler = sampler;
+        }
+        if let Some(filter) = self.filter {
+            image.filter = filter;
+            image.filter_radius = filter.default_radius();
+        }
+        if let Some(radius) = self.filter_radius {
+            if radius.is_nan() || radius <= 0.0 {
+                return Err("the filter radius must be positive".to_string());
+            }
+            image.filter_radius = radius;
+        }
+
+        Ok(())
+    }
+
+    // Overrides the default render settings with the command line ones
+    pub fn apply_render(
+        &self,
+        settings: &mut RenderSettings,
+        image: &ImageSettings,
+    ) -> Result<(), String> {
+        if let Some(tile_size) = self.tile_size {
+            if tile_size == 0 {
+                return Err("the tile size must be positive".to_string());
+            }
+            settings.tile_size = tile_size;
+        }
+        if let Some(tile_order) = self.tile_order {
+            settings.tile_order = tile_order;
+        }
+        if let Some(passes) = self.passes {
+            if passes == 0 {
+                return Err("at least one pass is required".to_string());
+            }
+            settings.passes = passes;
+        } else if self.time_limit.is_some() {
+            // Time limited renders stop closer to the limit with short passes
+            settings.passes = image.samples_per_pixel;
+        }
+
+        Ok(())
+    }
+
+    // Format of the heatmap, guessed from its file extension
+    pub fn heatmap_format(&self) -> Result<Option<OutputFormat>, String> {
+        match &self.heatmap {
+            Some(path) => OutputFormat::from_path(path)
+                .map(Some)
+                .ok_or_else(|| format!("cannot guess the format of heatmap '{}'", path.display())),
+            None => Ok(Option::None),
+        }
+    }
+
+    pub fn output_format(&self) -> Result<OutputFormat, String> {
+        match (self.format, &self.output) {
+            (Some(format), _) => Ok(format),
+            (None, Some(path)) => OutputFormat::from_path(path).ok_or_else(|| {
+                format!(
+                    "cannot guess the format of '{}', use --format",
+                    path.display()
+                )
+            }),
+            (None, None) => Ok(OutputFormat::Ppm),
+        }
+    }
+}
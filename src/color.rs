@@ -1,11 +1,13 @@
-use std::io::Write;
+//! Colors and their conversion to image pixels.
+
 use std::ops::RangeInclusive;
 
 use crate::geometry::Vec3;
 
+/// Linear RGB radiance, components may exceed 1.
 pub type Color = Vec3;
 
-pub type ValueRange = RangeInclusive<f32>;
+type ValueRange = RangeInclusive<f32>;
 
 fn clamp(val: f32, range: &ValueRange) -> f32 {
     if val < *range.start() {
@@ -17,19 +19,35 @@ fn clamp(val: f32, range: &ValueRange) -> f32 {
     }
 }
 
-pub fn write_color(w: &mut dyn Write, pixel_color: &Color, samples_per_pixel: i32) {
-    let clamp_range = 0.0f32..=0.999f32;
+// Apply gamma 2 correction
+fn gamma_corrected(pixel_color: &Color) -> Color {
+    Color::new(
+        pixel_color.x.max(0.0),
+        pixel_color.y.max(0.0),
+        pixel_color.z.max(0.0),
+    )
+    .sqrt()
+}
 
-    // Divide the color by the number of samples
-    let scale = 1.0 / (samples_per_pixel as f32);
-    let scaled_color = scale * pixel_color;
+// 8-bit sRGB-ish pixel, gamma corrected and clamped
+pub(crate) fn to_rgb8(pixel_color: &Color) -> [u8; 3] {
+    let clamp_range = 0.0f32..=0.999f32;
+    let corrected_color = gamma_corrected(pixel_color);
 
-    // Apply gamma 2 correction
-    let corrected_color = scaled_color.sqrt();
+    [
+        (256.0 * clamp(corrected_color.x, &clamp_range)) as u8,
+        (256.0 * clamp(corrected_color.y, &clamp_range)) as u8,
+        (256.0 * clamp(corrected_color.z, &clamp_range)) as u8,
+    ]
+}
 
-    let ir = (256.0 * clamp(corrected_color.x, &clamp_range)) as i32;
-    let ig = (256.0 * clamp(corrected_color.y, &clamp_range)) as i32;
-    let ib = (256.0 * clamp(corrected_color.z, &clamp_range)) as i32;
+pub(crate) fn to_rgb16(pixel_color: &Color) -> [u16; 3] {
+    let clamp_range = 0.0f32..=1.0f32;
+    let corrected_color = gamma_corrected(pixel_color);
 
-    writeln!(w, "{} {} {}", ir, ig, ib).expect("Failed to write to target stream!");
+    [
+        (65535.0 * clamp(corrected_color.x, &clamp_range)).round() as u16,
+        (65535.0 * clamp(corrected_color.y, &clamp_range)).round() as u16,
+        (65535.0 * clamp(corrected_color.z, &clamp_range)).round() as u16,
+    ]
 }
@@ -0,0 +1,148 @@
+//! Light reaching the scene from infinitely far away.
+
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::codecs::hdr::HdrDecoder;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::import::ImportError;
+
+// Radiance reaching the scene from infinitely far away, seen by every ray
+// leaving the scene
+pub trait Environment {
+    fn color(&self, dir: &Vec3) -> Color;
+}
+
+pub struct ConstantEnvironment {
+    pub color: Color,
+}
+
+impl ConstantEnvironment {
+    pub fn new(color: &Color) -> Self {
+        Self { color: *color }
+    }
+}
+
+impl Environment for ConstantEnvironment {
+    fn color(&self, _dir: &Vec3) -> Color {
+        self.color
+    }
+}
+
+// Vertical gradient, the sky of "Ray Tracing in One Weekend" by default
+pub struct GradientEnvironment {
+    pub bottom: Color,
+    pub top: Color,
+}
+
+impl GradientEnvironment {
+    pub fn new(bottom: &Color, top: &Color) -> Self {
+        Self {
+            bottom: *bottom,
+            top: *top,
+        }
+    }
+
+    pub fn sky() -> Self {
+        Self::new(&Color::new(1.0, 1.0, 1.0), &Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Environment for GradientEnvironment {
+    fn color(&self, dir: &Vec3) -> Color {
+        let unit_direction = Vec3::unit_vector(dir);
+        let t = 0.5 * (unit_direction.y + 1.0);
+        (1.0 - t) * self.bottom + t * self.top
+    }
+}
+
+// Equirectangular (latitude / longitude) radiance map, +y is up and the
+// center of the image looks towards -z before rotation
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    rotation: f32,
+    intensity: f32,
+}
+
+impl EnvironmentMap {
+    // Rotation in degrees around the vertical axis
+    pub fn new(
+        width: usize,
+        height: usize,
+        pixels: Vec<Color>,
+        rotation_deg: f32,
+        intensity: f32,
+    ) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "Invalid environment map size!"
+        );
+
+        Self {
+            width,
+            height,
+            pixels,
+            rotation: rotation_deg.to_radians(),
+            intensity,
+        }
+    }
+
+    // Loads a Radiance HDR file
+    pub fn load(path: &Path, rotation_deg: f32, intensity: f32) -> Result<Self, ImportError> {
+        let file = File::open(path).map_err(|err| ImportError::Io(path.to_path_buf(), err))?;
+        let decode_error =
+            |err: image::ImageError| ImportError::Decode(path.to_path_buf(), err.to_string());
+
+        let decoder = HdrDecoder::new(BufReader::new(file)).map_err(decode_error)?;
+        let metadata = decoder.metadata();
+        let pixels = decoder
+            .read_image_hdr()
+            .map_err(decode_error)?
+            .iter()
+            .map(|p| Color::new(p[0], p[1], p[2]))
+            .collect();
+
+        Ok(Self::new(
+            metadata.width as usize,
+            metadata.height as usize,
+            pixels,
+            rotation_deg,
+            intensity,
+        ))
+    }
+
+    fn texel(&self, x: usize, y: usize) -> &Color {
+        &self.pixels[y * self.width + x]
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn color(&self, dir: &Vec3) -> Color {
+        let unit_direction = Vec3::unit_vector(dir);
+
+        let phi = unit_direction.x.atan2(-unit_direction.z) + self.rotation;
+        let theta = unit_direction.y.clamp(-1.0, 1.0).acos();
+        let u = (phi / (2.0 * PI) + 0.5).rem_euclid(1.0);
+        let v = theta / PI;
+
+        // Bilinear filtering, wrapping around horizontally
+        let x = u * self.width as f32 - 0.5;
+        let y = (v * self.height as f32 - 0.5).max(0.0);
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        let x0 = (x.floor() as isize).rem_euclid(self.width as isize) as usize;
+        let x1 = (x0 + 1) % self.width;
+        let y0 = (y.floor() as usize).min(self.height - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+
+        let top = (1.0 - fx) * self.texel(x0, y0) + fx * self.texel(x1, y0);
+        let bottom = (1.0 - fx) * self.texel(x0, y1) + fx * self.texel(x1, y1);
+        self.intensity * ((1.0 - fy) * top + fy * bottom)
+    }
+}
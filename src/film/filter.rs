@@ -0,0 +1,227 @@
+//! Reconstruction filters, weighing samples by their distance to pixel centers.
+
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+// Weight of a sample at an offset from a pixel center, in pixels. Every
+// filter here is separable and vanishes beyond its radius.
+pub(crate) trait Filter {
+    fn radius(&self) -> f32;
+    fn evaluate(&self, x: f32, y: f32) -> f32;
+}
+
+/// Reconstruction filter spreading every sample over the pixels around it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FilterKind {
+    /// Every sample within the radius counts the same.
+    Box,
+    /// Weight falling linearly to zero at the radius.
+    Tent,
+    /// Gaussian shifted down to reach zero at the radius.
+    Gaussian,
+    /// Mitchell-Netravali cubic, sharper than the Gaussian with little
+    /// ringing.
+    Mitchell,
+    /// Windowed sinc, the sharpest with the most ringing.
+    Lanczos,
+}
+
+impl FilterKind {
+    /// Names of the filters on the command line and in scene files.
+    pub const NAMES: &'static [&'static str] = &["box", "tent", "gaussian", "mitchell", "lanczos"];
+
+    /// Usual radius of the filter, in pixels.
+    pub fn default_radius(self) -> f32 {
+        match self {
+            FilterKind::Box => 0.5,
+            FilterKind::Tent => 1.0,
+            FilterKind::Gaussian => 1.5,
+            FilterKind::Mitchell => 2.0,
+            FilterKind::Lanczos => 3.0,
+        }
+    }
+
+    // The filter with its usual parameters
+    pub(crate) fn build(self, radius: f32) -> Box<dyn Filter + Send + Sync> {
+        match self {
+            FilterKind::Box => Box::new(BoxFilter::new(radius)),
+            FilterKind::Tent => Box::new(TentFilter::new(radius)),
+            FilterKind::Gaussian => Box::new(GaussianFilter::new(radius, 0.5)),
+            FilterKind::Mitchell => Box::new(MitchellFilter::new(radius, 1.0 / 3.0, 1.0 / 3.0)),
+            FilterKind::Lanczos => Box::new(LanczosFilter::new(radius)),
+        }
+    }
+}
+
+impl FromStr for FilterKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "box" => Ok(FilterKind::Box),
+            "tent" => Ok(FilterKind::Tent),
+            "gaussian" => Ok(FilterKind::Gaussian),
+            "mitchell" => Ok(FilterKind::Mitchell),
+            "lanczos" => Ok(FilterKind::Lanczos),
+            _ => Err(format!("unknown filter '{}'", s)),
+        }
+    }
+}
+
+// Every sample counts the same, a radius of half a pixel keeps samples in
+// the pixel they fall in
+pub(crate) struct BoxFilter {
+    radius: f32,
+}
+
+impl BoxFilter {
+    pub(crate) fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+}
+
+impl Filter for BoxFilter {
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f32, y: f32) -> f32 {
+        if x.abs() <= self.radius && y.abs() <= self.radius {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+pub(crate) struct TentFilter {
+    radius: f32,
+}
+
+impl TentFilter {
+    pub(crate) fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+}
+
+impl Filter for TentFilter {
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f32, y: f32) -> f32 {
+        (self.radius - x.abs()).max(0.0) * (self.radius - y.abs()).max(0.0)
+    }
+}
+
+// Gaussian shifted down to reach zero at the radius, alpha is the falloff
+// rate
+pub(crate) struct GaussianFilter {
+    radius: f32,
+    alpha: f32,
+    edge: f32,
+}
+
+impl GaussianFilter {
+    pub(crate) fn new(radius: f32, alpha: f32) -> Self {
+        Self {
+            radius,
+            alpha,
+            edge: (-alpha * radius * radius).exp(),
+        }
+    }
+
+    fn gaussian(&self, x: f32) -> f32 {
+        ((-self.alpha * x * x).exp() - self.edge).max(0.0)
+    }
+}
+
+impl Filter for GaussianFilter {
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f32, y: f32) -> f32 {
+        self.gaussian(x) * self.gaussian(y)
+    }
+}
+
+// Mitchell-Netravali cubic, b = c = 1/3 is their recommended trade-off
+// between blurring and ringing
+pub(crate) struct MitchellFilter {
+    radius: f32,
+    b: f32,
+    c: f32,
+}
+
+impl MitchellFilter {
+    pub(crate) fn new(radius: f32, b: f32, c: f32) -> Self {
+        Self { radius, b, c }
+    }
+
+    // The cubic spans [-2, 2]
+    fn mitchell(&self, x: f32) -> f32 {
+        let x = (2.0 * x / self.radius).abs();
+        let (b, c) = (self.b, self.c);
+        let value = if x > 2.0 {
+            0.0
+        } else if x > 1.0 {
+            (-b - 6.0 * c) * x * x * x
+                + (6.0 * b + 30.0 * c) * x * x
+                + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        } else {
+            (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+                + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                + (6.0 - 2.0 * b)
+        };
+        value / 6.0
+    }
+}
+
+impl Filter for MitchellFilter {
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f32, y: f32) -> f32 {
+        self.mitchell(x) * self.mitchell(y)
+    }
+}
+
+// Sinc windowed by a wider sinc, as many lobes as the radius
+pub(crate) struct LanczosFilter {
+    radius: f32,
+}
+
+impl LanczosFilter {
+    pub(crate) fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+
+    fn lanczos(&self, x: f32) -> f32 {
+        let sinc = |x: f32| {
+            if x.abs() < 1e-5 {
+                1.0
+            } else {
+                (PI * x).sin() / (PI * x)
+            }
+        };
+
+        if x.abs() > self.radius {
+            0.0
+        } else {
+            sinc(x) * sinc(x / self.radius)
+        }
+    }
+}
+
+impl Filter for LanczosFilter {
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f32, y: f32) -> f32 {
+        self.lanczos(x) * self.lanczos(y)
+    }
+}
@@ -0,0 +1,163 @@
+//! Reconstruction of pixel values from samples.
+//!
+//! Samples are spread over the pixels around them by one of the
+//! reconstruction filters of [`filter::FilterKind`].
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::film::filter::Filter;
+use crate::output::framebuffer::Framebuffer;
+
+pub mod filter;
+
+// Sum of the samples reaching a pixel weighted by the filter, and of their
+// weights
+#[derive(Copy, Clone, Default)]
+pub(crate) struct FilmPixel {
+    pub(crate) weighted_sum: Color,
+    pub(crate) weight: f32,
+}
+
+// Accumulates samples spread over the pixels around them by a
+// reconstruction filter. Positions are in pixels from the top left corner of
+// the image, pixel (x, y) covers [x, x + 1) x [y, y + 1).
+pub(crate) struct Film {
+    width: usize,
+    height: usize,
+    filter: Arc<dyn Filter + Send + Sync>,
+    pixels: Vec<FilmPixel>,
+}
+
+impl Film {
+    pub(crate) fn new(width: usize, height: usize, filter: Box<dyn Filter + Send + Sync>) -> Self {
+        Film {
+            width,
+            height,
+            filter: Arc::from(filter),
+            pixels: vec![FilmPixel::default(); width * height],
+        }
+    }
+
+    // Number of pixels around the one a sample is drawn in that the filter
+    // spreads it over
+    pub(crate) fn reach(&self) -> usize {
+        (self.filter.radius() - 0.5).ceil().max(0.0) as usize
+    }
+
+    // Pixels of the film reached by samples drawn within the given ones, to
+    // be filled separately, possibly by another thread, and merged back
+    pub(crate) fn tile(&self, columns: Range<usize>, rows: Range<usize>) -> FilmTile {
+        let reach = self.reach();
+        let columns = columns.start.saturating_sub(reach)..(columns.end + reach).min(self.width);
+        let rows = rows.start.saturating_sub(reach)..(rows.end + reach).min(self.height);
+
+        FilmTile {
+            filter: self.filter.clone(),
+            pixels: vec![FilmPixel::default(); columns.len() * rows.len()],
+            columns,
+            rows,
+        }
+    }
+
+    // Pixels row by row from the top of the image
+    pub(crate) fn pixels(&self) -> &[FilmPixel] {
+        &self.pixels
+    }
+
+    pub(crate) fn pixels_mut(&mut self) -> &mut [FilmPixel] {
+        &mut self.pixels
+    }
+
+    pub(crate) fn merge(&mut self, tile: FilmTile) {
+        let width = self.width;
+        let tile_width = tile.columns.len();
+        for (row, tile_row) in tile.rows.clone().zip(tile.pixels.chunks(tile_width)) {
+            let start = row * width + tile.columns.start;
+            let film_row = &mut self.pixels[start..start + tile_width];
+            for (pixel, tile_pixel) in film_row.iter_mut().zip(tile_row) {
+                pixel.weighted_sum = pixel.weighted_sum + tile_pixel.weighted_sum;
+                pixel.weight += tile_pixel.weight;
+            }
+        }
+    }
+
+    // Weighted average of the samples of every pixel
+    pub(crate) fn to_framebuffer(&self) -> Framebuffer {
+        let mut image = Framebuffer::new(self.width, self.height);
+        image
+            .rows_mut()
+            .zip(self.pixels.chunks(self.width))
+            .for_each(|(row, film_row)| {
+                row.iter_mut()
+                    .zip(film_row)
+                    .for_each(|(pixel, film_pixel)| {
+                        if film_pixel.weight != 0.0 {
+                            *pixel = film_pixel.weighted_sum / film_pixel.weight;
+                        }
+                    })
+            });
+        image
+    }
+}
+
+pub(crate) struct FilmTile {
+    filter: Arc<dyn Filter + Send + Sync>,
+    columns: Range<usize>,
+    rows: Range<usize>,
+    pixels: Vec<FilmPixel>,
+}
+
+impl FilmTile {
+    // Adds the sample to every pixel of the tile within the filter radius
+    pub(crate) fn add_sample(&mut self, (x, y): (f32, f32), color: &Color) {
+        let radius = self.filter.radius();
+        let pixel_range = |position: f32, range: &Range<usize>| {
+            let first = (position - 0.5 - radius).ceil().max(range.start as f32) as usize;
+            let last = (position - 0.5 + radius)
+                .floor()
+                .min(range.end as f32 - 1.0);
+            if last < first as f32 {
+                first..first
+            } else {
+                first..last as usize + 1
+            }
+        };
+
+        let width = self.columns.len();
+        for row in pixel_range(y, &self.rows) {
+            for column in pixel_range(x, &self.columns) {
+                let weight = self
+                    .filter
+                    .evaluate(x - (column as f32 + 0.5), y - (row as f32 + 0.5));
+                if weight == 0.0 {
+                    continue;
+                }
+
+                let index = (row - self.rows.start) * width + column - self.columns.start;
+                let pixel = &mut self.pixels[index];
+                pixel.weighted_sum = pixel.weighted_sum + weight * color;
+                pixel.weight += weight;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::film::filter::FilterKind;
+
+    #[test]
+    fn sample_at_pixel_center_keeps_its_color() {
+        let mut film = Film::new(4, 4, FilterKind::Tent.build(1.0));
+        let mut tile = film.tile(0..4, 0..4);
+        tile.add_sample((1.5, 1.5), &Color::new(1.0, 1.0, 1.0));
+        film.merge(tile);
+
+        let image = film.to_framebuffer();
+        assert_eq!(image.pixels()[4 + 1], Color::new(1.0, 1.0, 1.0));
+        assert_eq!(image.pixels()[0], Color::new(0.0, 0.0, 0.0));
+    }
+}
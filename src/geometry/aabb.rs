@@ -0,0 +1,56 @@
+use crate::geometry::hittable::HitRange;
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Aabb {
+    pub fn new(min: Point3, max: Point3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn surrounding_box(box0: &Self, box1: &Self) -> Self {
+        Self {
+            min: Vec3::min(&box0.min, &box1.min),
+            max: Vec3::max(&box0.max, &box1.max),
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    // Slab test, the caller provides the precomputed inverse of the ray direction
+    pub fn hit(&self, r: &Ray, inv_dir: &Vec3, range: &HitRange) -> bool {
+        let mut t_min = *range.start();
+        let mut t_max = *range.end();
+
+        for axis in 0..3 {
+            let t0 = (self.min[axis] - r.orig[axis]) * inv_dir[axis];
+            let t1 = (self.max[axis] - r.orig[axis]) * inv_dir[axis];
+            let (t0, t1) = if inv_dir[axis] < 0.0 {
+                (t1, t0)
+            } else {
+                (t0, t1)
+            };
+
+            // f32::max and f32::min discard NaNs produced by 0 * inf
+            t_min = t0.max(t_min);
+            t_max = t1.min(t_max);
+            if t_max < t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,53 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable, HittableList};
+use crate::geometry::quad::Quad;
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Axis-aligned box made of six outward facing quads
+pub struct BoxShape {
+    sides: HittableList<'static>,
+    bbox: Aabb,
+}
+
+impl BoxShape {
+    pub fn new(
+        p0: Point3,
+        p1: Point3,
+        material: impl Fn() -> Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        let min = Vec3::min(&p0, &p1);
+        let max = Vec3::max(&p0, &p1);
+        let dx = Vec3::new(max.x - min.x, 0.0, 0.0);
+        let dy = Vec3::new(0.0, max.y - min.y, 0.0);
+        let dz = Vec3::new(0.0, 0.0, max.z - min.z);
+
+        let mut sides = HittableList::new();
+        for (q, u, v) in [
+            (Point3::new(min.x, min.y, max.z), dx, dy),
+            (Point3::new(max.x, min.y, max.z), -dz, dy),
+            (Point3::new(max.x, min.y, min.z), -dx, dy),
+            (Point3::new(min.x, min.y, min.z), dz, dy),
+            (Point3::new(min.x, max.y, max.z), dx, -dz),
+            (Point3::new(min.x, min.y, min.z), dx, dz),
+        ] {
+            sides.push(Box::new(Quad::new(q, u, v, material())));
+        }
+
+        BoxShape {
+            sides,
+            bbox: Aabb::new(min, max),
+        }
+    }
+}
+
+impl Hittable for BoxShape {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        self.sides.hit(r, range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::Some(self.bbox)
+    }
+}
@@ -0,0 +1,366 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable, HittableList};
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+
+// Surface Area Heuristic parameters, costs are relative to a primitive intersection
+const BIN_COUNT: usize = 12;
+const TRAVERSAL_COST: f32 = 0.125;
+const MAX_PRIMITIVES_IN_LEAF: usize = 4;
+
+// Also bounds the size of the traversal stack
+const MAX_DEPTH: usize = 64;
+
+enum BvhNode {
+    Leaf {
+        bbox: Aabb,
+        first: usize,
+        count: usize,
+    },
+    // The left child is stored right after its parent
+    Interior {
+        bbox: Aabb,
+        right: usize,
+        axis: usize,
+    },
+}
+
+impl BvhNode {
+    fn bbox(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bbox, .. } => bbox,
+            BvhNode::Interior { bbox, .. } => bbox,
+        }
+    }
+}
+
+struct PrimitiveInfo {
+    index: usize,
+    bbox: Aabb,
+    centroid: Point3,
+}
+
+struct Split {
+    cost: f32,
+    axis: usize,
+    bin: usize,
+    low: f32,
+    extent: f32,
+}
+
+pub struct Bvh<'a> {
+    nodes: Vec<BvhNode>,
+    objects: HittableList<'a>,
+    unbounded: HittableList<'a>,
+}
+
+impl<'a> Bvh<'a> {
+    pub fn new(mut list: HittableList<'a>) -> Self {
+        let mut bounded = vec![];
+        let mut infos = vec![];
+        let mut unbounded = HittableList::new();
+
+        for hittable in list.drain(..) {
+            if let Some(bbox) = hittable.bounding_box() {
+                infos.push(PrimitiveInfo {
+                    index: bounded.len(),
+                    bbox,
+                    centroid: bbox.centroid(),
+                });
+                bounded.push(Some(hittable));
+            } else {
+                (*unbounded).push(hittable);
+            }
+        }
+
+        let mut nodes = vec![];
+        if !infos.is_empty() {
+            Self::build(&mut nodes, &mut infos, 0, 0);
+        }
+
+        // Store objects in leaf order
+        let mut objects = HittableList::new();
+        infos.iter().for_each(|info| {
+            (*objects).push(bounded[info.index].take().unwrap());
+        });
+
+        Self {
+            nodes,
+            objects,
+            unbounded,
+        }
+    }
+
+    fn build(
+        nodes: &mut Vec<BvhNode>,
+        prims: &mut [PrimitiveInfo],
+        first: usize,
+        depth: usize,
+    ) -> usize {
+        let bbox = prims
+            .iter()
+            .skip(1)
+            .fold(prims[0].bbox, |acc, p| Aabb::surrounding_box(&acc, &p.bbox));
+        let node_index = nodes.len();
+
+        let split = if prims.len() > 1 && depth + 1 < MAX_DEPTH {
+            Self::partition(prims, &bbox)
+        } else {
+            Option::None
+        };
+
+        match split {
+            Some((axis, mid)) => {
+                nodes.push(BvhNode::Interior {
+                    bbox,
+                    right: 0,
+                    axis,
+                });
+
+                let (left_prims, right_prims) = prims.split_at_mut(mid);
+                Self::build(nodes, left_prims, first, depth + 1);
+                let right_index = Self::build(nodes, right_prims, first + mid, depth + 1);
+
+                if let BvhNode::Interior { right, .. } = &mut nodes[node_index] {
+                    *right = right_index;
+                }
+            }
+            None => nodes.push(BvhNode::Leaf {
+                bbox,
+                first,
+                count: prims.len(),
+            }),
+        }
+
+        node_index
+    }
+
+    fn bin_index(centroid: &Point3, split: &Split) -> usize {
+        let relative = (centroid[split.axis] - split.low) / split.extent;
+        ((relative * BIN_COUNT as f32) as usize).min(BIN_COUNT - 1)
+    }
+
+    // Reorders the primitives around the cheapest binned SAH split.
+    // Returns the split axis and the index of the first right primitive, or None
+    // when a leaf is cheaper.
+    fn partition(prims: &mut [PrimitiveInfo], bbox: &Aabb) -> Option<(usize, usize)> {
+        let centroid_bounds = prims
+            .iter()
+            .fold(Aabb::new(prims[0].centroid, prims[0].centroid), |acc, p| {
+                Aabb::surrounding_box(&acc, &Aabb::new(p.centroid, p.centroid))
+            });
+        let parent_area = bbox.surface_area().max(f32::EPSILON);
+        let merge = |acc: Option<Aabb>, bbox: &Option<Aabb>| match (acc, bbox) {
+            (Some(a), Some(b)) => Some(Aabb::surrounding_box(&a, b)),
+            (a, b) => a.or(*b),
+        };
+
+        let mut best: Option<Split> = Option::None;
+        for axis in 0..3 {
+            let low = centroid_bounds.min[axis];
+            let extent = centroid_bounds.max[axis] - low;
+            if extent <= 0.0 {
+                continue;
+            }
+            let mut candidate = Split {
+                cost: f32::INFINITY,
+                axis,
+                bin: 0,
+                low,
+                extent,
+            };
+
+            let mut bins = [(0usize, Option::<Aabb>::None); BIN_COUNT];
+            prims.iter().for_each(|p| {
+                let bin = &mut bins[Self::bin_index(&p.centroid, &candidate)];
+                bin.0 += 1;
+                bin.1 = merge(bin.1, &Some(p.bbox));
+            });
+
+            // Sweep from the right to get the cost of every right partition
+            let mut right_counts = [0usize; BIN_COUNT];
+            let mut right_areas = [0.0f32; BIN_COUNT];
+            let (mut count, mut acc) = (0, Option::None);
+            for i in (1..BIN_COUNT).rev() {
+                count += bins[i].0;
+                acc = merge(acc, &bins[i].1);
+                right_counts[i] = count;
+                right_areas[i] = acc.map_or(0.0, |b| b.surface_area());
+            }
+
+            let (mut count, mut acc) = (0, Option::None);
+            for i in 0..BIN_COUNT - 1 {
+                count += bins[i].0;
+                acc = merge(acc, &bins[i].1);
+                if count == 0 || right_counts[i + 1] == 0 {
+                    continue;
+                }
+
+                let left_area = acc.map_or(0.0, |b| b.surface_area());
+                let cost = TRAVERSAL_COST
+                    + (count as f32 * left_area + right_counts[i + 1] as f32 * right_areas[i + 1])
+                        / parent_area;
+                if cost < candidate.cost {
+                    candidate.cost = cost;
+                    candidate.bin = i + 1;
+                }
+            }
+
+            if best.as_ref().is_none_or(|b| candidate.cost < b.cost) {
+                best = Some(candidate);
+            }
+        }
+
+        match best {
+            Some(split)
+                if split.cost < prims.len() as f32 || prims.len() > MAX_PRIMITIVES_IN_LEAF =>
+            {
+                let mut mid = 0;
+                for i in 0..prims.len() {
+                    if Self::bin_index(&prims[i].centroid, &split) < split.bin {
+                        prims.swap(i, mid);
+                        mid += 1;
+                    }
+                }
+                Some((split.axis, mid))
+            }
+            // All centroids coincide, any split is as good as another
+            None if prims.len() > MAX_PRIMITIVES_IN_LEAF => Some((0, prims.len() / 2)),
+            _ => Option::None,
+        }
+    }
+}
+
+impl Hittable for Bvh<'_> {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let mut hit_record = self.unbounded.hit(r, range.clone());
+        let mut closest_so_far = hit_record.as_ref().map_or(*range.end(), |rec| rec.t());
+
+        if self.nodes.is_empty() {
+            return hit_record;
+        }
+
+        let inv_dir = Vec3::new(1.0 / r.dir.x, 1.0 / r.dir.y, 1.0 / r.dir.z);
+        let mut stack = [0usize; MAX_DEPTH];
+        let mut stack_size = 0;
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+            let mut next = Option::None;
+
+            let reduced_range = HitRange::new(*range.start(), closest_so_far);
+            if node.bbox().hit(r, &inv_dir, &reduced_range) {
+                match *node {
+                    BvhNode::Leaf { first, count, .. } => {
+                        self.objects[first..first + count]
+                            .iter()
+                            .for_each(|hittable| {
+                                let reduced_range = HitRange::new(*range.start(), closest_so_far);
+                                if let Some(rec) = hittable.hit(r, reduced_range) {
+                                    closest_so_far = rec.t();
+                                    hit_record = Some(rec);
+                                }
+                            });
+                    }
+                    BvhNode::Interior { right, axis, .. } => {
+                        // Visit the child nearest to the ray origin first
+                        let (near, far) = if r.dir[axis] < 0.0 {
+                            (right, current + 1)
+                        } else {
+                            (current + 1, right)
+                        };
+                        stack[stack_size] = far;
+                        stack_size += 1;
+                        next = Some(near);
+                    }
+                }
+            }
+
+            current = match next {
+                Some(index) => index,
+                None if stack_size > 0 => {
+                    stack_size -= 1;
+                    stack[stack_size]
+                }
+                None => break,
+            };
+        }
+
+        hit_record
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        if self.unbounded.is_empty() {
+            self.nodes.first().map(|node| *node.bbox())
+        } else {
+            Option::None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::scene::Scene;
+
+    // The BVH must find the same closest hits as testing every object
+    fn check_against_linear(scene: impl Fn() -> Scene) {
+        let linear = scene().world;
+        let bvh = Bvh::new(scene().world);
+        let bbox = bvh.bounding_box().unwrap_or_else(|| {
+            Aabb::new(
+                Point3::new(-10.0, -10.0, -10.0),
+                Point3::new(10.0, 10.0, 10.0),
+            )
+        });
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut hits = 0;
+        for _ in 0..20_000 {
+            let mut random_point = || {
+                let t = Vec3::new(rng.gen(), rng.gen(), rng.gen());
+                bbox.min + t * (bbox.max - bbox.min)
+            };
+            let orig = random_point();
+            let dir = random_point() - orig;
+            let r = Ray::new(orig, dir, 0.0);
+
+            let range = HitRange::new(0.001, f32::INFINITY);
+            let expected = linear.hit(&r, range.clone()).map(|rec| rec.t());
+            let actual = bvh.hit(&r, range).map(|rec| rec.t());
+            assert_eq!(
+                actual, expected,
+                "ray from {:?} towards {:?}",
+                r.orig, r.dir
+            );
+            hits += expected.is_some() as usize;
+        }
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn matches_linear_list_on_random_scene() {
+        check_against_linear(|| Scene::random(&mut StdRng::seed_from_u64(1)));
+    }
+
+    #[test]
+    fn matches_linear_list_on_cornell_smoke() {
+        check_against_linear(Scene::cornell_smoke);
+    }
+
+    #[test]
+    fn matches_linear_list_on_scene_files() {
+        for file in &["csg.toml", "primitives.toml", "motion_blur.toml"] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("scenes")
+                .join(file);
+            check_against_linear(|| Scene::load(&path).unwrap());
+        }
+    }
+}
@@ -1,7 +1,34 @@
 use crate::geometry::ray::Ray;
 use crate::geometry::{Point3, Vec3};
+use crate::sampler::{unit_disk, Sampler};
 
-#[allow(dead_code)]
+/// Thin lens camera, seeing the image from its bottom left corner to its top
+/// right one.
+///
+/// ```
+/// use raytracing::{Camera, Point3, RenderSettings, Renderer, Scene, Vec3};
+///
+/// let mut scene = Scene::three_spheres();
+/// scene.image.width = 16;
+/// scene.image.height = 9;
+/// scene.image.samples_per_pixel = 2;
+///
+/// // Straight at the middle sphere, everything in focus
+/// let camera = Camera::new(
+///     Point3::new(0.0, 0.0, 1.0),
+///     Point3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     60.0,
+///     scene.image.aspect_ratio(),
+///     0.0,
+///     2.0,
+/// );
+/// assert_eq!(camera.view_direction(), Vec3::new(0.0, 0.0, -1.0));
+///
+/// let mut renderer = Renderer::new(scene, &RenderSettings::default()).with_camera(camera);
+/// while renderer.render_pass() {}
+/// assert_eq!(renderer.image().width(), 16);
+/// ```
 pub struct Camera {
     origin: Point3,
     lower_left_corner: Point3,
@@ -9,11 +36,16 @@ pub struct Camera {
     vertical: Vec3,
     u: Vec3,
     v: Vec3,
-    w: Vec3,
     lens_radius: f32,
+    time0: f32,
+    time1: f32,
 }
 
 impl Camera {
+    /// Camera at `lookfrom` looking at `lookat`, with `vup` up in the image.
+    /// The vertical field of view is in degrees, the aspect ratio is the
+    /// width over the height of the image. The lens is `aperture` wide and
+    /// in focus at `focus_dist`, everything is in focus without aperture.
     pub fn new(
         lookfrom: Point3,
         lookat: Point3,
@@ -46,18 +78,68 @@ impl Camera {
             lower_left_corner: origin - horizontal / 2.0 - vertical / 2.0 - focus_dist * w,
             u,
             v,
-            w,
             lens_radius,
+            time0: 0.0,
+            time1: 0.0,
         }
     }
 
-    pub fn get_ray(&self, s: f32, t: f32) -> Ray {
-        let rd = self.lens_radius * Vec3::random_in_unit_disk();
-        let offset = self.u * rd.x + self.v * rd.y;
+    /// The camera with its shutter open from `time0` to `time1`, rays are
+    /// spread uniformly over that time.
+    pub fn with_shutter(self, time0: f32, time1: f32) -> Self {
+        Self {
+            time0,
+            time1,
+            ..self
+        }
+    }
+
+    /// Center of the lens.
+    pub fn origin(&self) -> Point3 {
+        self.origin
+    }
+
+    /// Unit direction the camera looks in, through the center of the image.
+    pub fn view_direction(&self) -> Vec3 {
+        let center = self.lower_left_corner + self.horizontal / 2.0 + self.vertical / 2.0;
+        Vec3::unit_vector(&(center - self.origin))
+    }
+
+    // Ray through the point (s, t) of the image, from its bottom left corner
+    // (0, 0) to its top right one (1, 1)
+    pub(crate) fn get_ray(&self, s: f32, t: f32, sampler: &mut dyn Sampler) -> Ray {
+        let (x, y) = unit_disk(sampler.get_2d());
+        let offset = self.lens_radius * (self.u * x + self.v * y);
 
         Ray::new(
             self.origin + offset,
             self.lower_left_corner + s * self.horizontal + t * self.vertical - self.origin - offset,
+            self.time0 + sampler.get_1d() * (self.time1 - self.time0),
         )
+        .with_sample(sampler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::SamplerKind;
+
+    #[test]
+    fn center_ray_looks_at_target() {
+        let camera = Camera::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.0,
+            0.0,
+            1.0,
+        );
+
+        let mut sampler = SamplerKind::Independent.build(1, 0);
+        sampler.start_pixel_sample((0, 0), 0);
+        let r = camera.get_ray(0.5, 0.5, sampler.as_mut());
+        assert_eq!(r.dir, Vec3::new(0.0, 0.0, -1.0));
     }
 }
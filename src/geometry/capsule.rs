@@ -0,0 +1,97 @@
+use std::f32::consts::PI;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::cylinder::{azimuth, hit_tube, nearest, LocalHit};
+use crate::geometry::frame::Frame;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::sphere::sphere_box;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Points within the radius of the segment between the two centers
+pub struct Capsule {
+    frame: Frame,
+    height: f32,
+    pub radius: f32,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Capsule {
+    pub fn new(base: Point3, top: Point3, r: f32, m: Box<dyn Material + Send + Sync>) -> Self {
+        Capsule {
+            frame: Frame::new(base, &(top - base)),
+            height: (top - base).length(),
+            radius: r.abs(),
+            material: m,
+        }
+    }
+
+    // Hits with the half of the sphere centered on the axis at height y
+    // that lies beyond the tube
+    fn hit_cap(&self, local: &Ray, y: f32, up: bool) -> [Option<LocalHit>; 2] {
+        let oc = local.orig - Vec3::new(0.0, y, 0.0);
+        let a = local.dir.length_squared();
+        let half_b = Vec3::dot(&oc, &local.dir);
+        let c = oc.length_squared() - self.radius * self.radius;
+
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return [Option::None, Option::None];
+        }
+
+        // v is the arc length from the bottom pole over the whole profile
+        let length = self.height + PI * self.radius;
+        let sqrtd = discriminant.sqrt();
+        [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a].map(|t| {
+            let p = local.at(t);
+            let normal = (p - Vec3::new(0.0, y, 0.0)) / self.radius;
+            if (normal.y > 0.0) != up {
+                return Option::None;
+            }
+
+            let theta = (-normal.y).clamp(-1.0, 1.0).acos();
+            let arc = if up {
+                self.height + self.radius * theta
+            } else {
+                self.radius * theta
+            };
+            Option::Some((t, normal, (azimuth(&p), arc / length)))
+        })
+    }
+}
+
+impl Hittable for Capsule {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local_ray(r);
+        let length = self.height + PI * self.radius;
+
+        // Rescale v along the tube to its share of the whole profile
+        let tube = hit_tube(&local, self.height, self.radius).map(|hit| {
+            hit.map(|(t, normal, (u, v))| {
+                let arc = 0.5 * PI * self.radius + v * self.height;
+                (t, normal, (u, arc / length))
+            })
+        });
+        let [bottom0, bottom1] = self.hit_cap(&local, 0.0, false);
+        let [top0, top1] = self.hit_cap(&local, self.height, true);
+        let (t, normal, uv) = nearest([tube[0], tube[1], bottom0, bottom1, top0, top1], &range)?;
+
+        Option::Some(HitRecord::new(
+            r.at(t),
+            self.material.as_ref(),
+            &self.frame.to_world_vector(&normal),
+            t,
+            uv,
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let top = self.frame.origin + self.height * self.frame.axis;
+        Option::Some(Aabb::surrounding_box(
+            &sphere_box(&self.frame.origin, self.radius),
+            &sphere_box(&top, self.radius),
+        ))
+    }
+}
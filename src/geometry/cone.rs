@@ -0,0 +1,108 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::cylinder::{azimuth, hit_cap, nearest};
+use crate::geometry::disk::disk_box;
+use crate::geometry::frame::Frame;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Capped cone, truncated when both radii are positive
+pub struct Cone {
+    frame: Frame,
+    height: f32,
+    pub base_radius: f32,
+    pub top_radius: f32,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point3,
+        top: Point3,
+        base_radius: f32,
+        top_radius: f32,
+        m: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        Cone {
+            frame: Frame::new(base, &(top - base)),
+            height: (top - base).length(),
+            base_radius: base_radius.abs(),
+            top_radius: top_radius.abs(),
+            material: m,
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local_ray(r);
+        let (o, d) = (&local.orig, &local.dir);
+
+        // The radius changes linearly with y: x^2 + z^2 = (r0 + k y)^2
+        let k = (self.top_radius - self.base_radius) / self.height;
+        let radius_o = self.base_radius + k * o.y;
+        let a = d.x * d.x + d.z * d.z - k * k * d.y * d.y;
+        let half_b = o.x * d.x + o.z * d.z - k * radius_o * d.y;
+        let c = o.x * o.x + o.z * o.z - radius_o * radius_o;
+
+        let side_roots = if a.abs() < 1e-8 {
+            // The ray is parallel to the slant of the cone
+            [(half_b != 0.0).then(|| -c / (2.0 * half_b)), Option::None]
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant < 0.0 {
+                [Option::None, Option::None]
+            } else {
+                let sqrtd = discriminant.sqrt();
+                [
+                    Option::Some((-half_b - sqrtd) / a),
+                    Option::Some((-half_b + sqrtd) / a),
+                ]
+            }
+        };
+        let side = side_roots.map(|root| {
+            let t = root?;
+            let p = local.at(t);
+            if !(0.0..=self.height).contains(&p.y) {
+                return Option::None;
+            }
+
+            // Gradient of the implicit surface, pointing up at a tip
+            let normal = Vec3::new(p.x, -k * (self.base_radius + k * p.y), p.z);
+            let normal = if normal.near_zero() {
+                Vec3::new(0.0, 1.0f32.copysign(-k), 0.0)
+            } else {
+                Vec3::unit_vector(&normal)
+            };
+            Option::Some((t, normal, (azimuth(&p), p.y / self.height)))
+        });
+
+        let (t, normal, uv) = nearest(
+            [
+                side[0],
+                side[1],
+                hit_cap(&local, 0.0, self.base_radius, false),
+                hit_cap(&local, self.height, self.top_radius, true),
+            ],
+            &range,
+        )?;
+
+        Option::Some(HitRecord::new(
+            r.at(t),
+            self.material.as_ref(),
+            &self.frame.to_world_vector(&normal),
+            t,
+            uv,
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let top = self.frame.origin + self.height * self.frame.axis;
+        Option::Some(Aabb::surrounding_box(
+            &disk_box(&self.frame.origin, &self.frame.axis, self.base_radius),
+            &disk_box(&top, &self.frame.axis, self.top_radius),
+        ))
+    }
+}
@@ -0,0 +1,108 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::Vec3;
+use crate::material::Material;
+use crate::sampler::{hash, to_unit_float};
+
+// A volume of uniform density filling a closed, convex boundary
+pub struct ConstantMedium<'a> {
+    boundary: Box<dyn Hittable + Send + Sync + 'a>,
+    neg_inv_density: f32,
+    phase_function: Box<dyn Material + Send + Sync>,
+}
+
+impl<'a> ConstantMedium<'a> {
+    pub fn new(
+        boundary: Box<dyn Hittable + Send + Sync + 'a>,
+        density: f32,
+        phase_function: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium<'_> {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        // Find where the ray enters and leaves the boundary, even behind its origin
+        let entry = self
+            .boundary
+            .hit(r, HitRange::new(f32::NEG_INFINITY, f32::INFINITY))?
+            .t();
+        let exit = self
+            .boundary
+            .hit(r, HitRange::new(entry + 0.0001, f32::INFINITY))?
+            .t();
+
+        let t_enter = entry.max(*range.start()).max(0.0);
+        let t_exit = exit.min(*range.end());
+        if t_enter >= t_exit {
+            return Option::None;
+        }
+
+        let ray_length = r.dir.length();
+        let distance_inside = (t_exit - t_enter) * ray_length;
+        // Drawn from the sample the ray carries, mixed with where it enters
+        // so that the media along a ray do not scatter it alike
+        let bits = hash(&[r.sample, entry.to_bits() as u64]);
+        let hit_distance = self.neg_inv_density * to_unit_float(bits as u32).ln();
+        if hit_distance > distance_inside {
+            return Option::None;
+        }
+
+        // The normal and the face are arbitrary inside a volume
+        let t = t_enter + hit_distance / ray_length;
+        Option::Some(HitRecord::new(
+            r.at(t),
+            self.phase_function.as_ref(),
+            &Vec3::new(1.0, 0.0, 0.0),
+            t,
+            (0.0, 0.0),
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::geometry::sphere::Sphere;
+    use crate::geometry::Point3;
+    use crate::material::isotropic::Isotropic;
+    use crate::material::lambertian::Lambertian;
+
+    #[test]
+    fn scatters_with_the_ray_sample() {
+        let white = Color::new(1.0, 1.0, 1.0);
+        let boundary = Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Box::new(Lambertian::new(&white)),
+        );
+        let medium = ConstantMedium::new(Box::new(boundary), 0.5, Box::new(Isotropic::new(&white)));
+
+        let count = 10_000;
+        let hits = (0..count)
+            .filter(|&sample| {
+                let mut r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+                r.sample = sample;
+                medium
+                    .hit(&r, HitRange::new(0.001, f32::INFINITY))
+                    .is_some()
+            })
+            .count();
+
+        // Crossing the diameter, the ray scatters with probability 1 - e^-1
+        let expected = 1.0 - (-1.0f32).exp();
+        assert!((hits as f32 / count as f32 - expected).abs() < 0.02);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable, Span};
+use crate::geometry::ray::Ray;
+use crate::geometry::Vec3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    fn inside(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+// Boolean combination of two closed solids
+pub struct Csg<'a> {
+    operation: CsgOperation,
+    left: Box<dyn Hittable + Send + Sync + 'a>,
+    right: Box<dyn Hittable + Send + Sync + 'a>,
+}
+
+impl<'a> Csg<'a> {
+    pub fn new(
+        operation: CsgOperation,
+        left: Box<dyn Hittable + Send + Sync + 'a>,
+        right: Box<dyn Hittable + Send + Sync + 'a>,
+    ) -> Self {
+        Self {
+            operation,
+            left,
+            right,
+        }
+    }
+}
+
+impl Hittable for Csg<'_> {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        self.spans(r)
+            .into_iter()
+            .flat_map(|span| [span.entry, span.exit])
+            .find(|rec| range.contains(&rec.t()))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let left = self.left.bounding_box()?;
+        let right = self.right.bounding_box()?;
+        match self.operation {
+            CsgOperation::Union => Option::Some(Aabb::surrounding_box(&left, &right)),
+            CsgOperation::Intersection => {
+                let min = Vec3::max(&left.min, &right.min);
+                Option::Some(Aabb::new(
+                    min,
+                    Vec3::max(&min, &Vec3::min(&left.max, &right.max)),
+                ))
+            }
+            CsgOperation::Difference => Option::Some(left),
+        }
+    }
+
+    // Sweeps the boundaries of both children along the ray while tracking
+    // whether it is inside of each
+    fn spans(&self, r: &Ray) -> Vec<Span<'_>> {
+        let mut boundaries: Vec<(HitRecord, bool)> = vec![];
+        for (spans, is_left) in [(self.left.spans(r), true), (self.right.spans(r), false)] {
+            for span in spans {
+                boundaries.push((span.entry, is_left));
+                boundaries.push((span.exit, is_left));
+            }
+        }
+        boundaries.sort_by(|a, b| a.0.t().total_cmp(&b.0.t()));
+
+        let (mut in_left, mut in_right) = (false, false);
+        let mut entry: Option<HitRecord> = Option::None;
+        let mut spans = vec![];
+        for (rec, is_left) in boundaries {
+            let was_inside = self.operation.inside(in_left, in_right);
+            if is_left {
+                in_left = rec.front_face();
+            } else {
+                in_right = rec.front_face();
+            }
+            let inside = self.operation.inside(in_left, in_right);
+
+            // Surfaces of the subtracted solid face the other way
+            let rec = if !is_left && self.operation == CsgOperation::Difference {
+                rec.flipped()
+            } else {
+                rec
+            };
+            if inside && !was_inside {
+                entry = Option::Some(rec);
+            } else if was_inside && !inside {
+                if let Some(entry) = entry.take() {
+                    spans.push(Span { entry, exit: rec });
+                }
+            }
+        }
+
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::geometry::sphere::Sphere;
+    use crate::geometry::Point3;
+    use crate::material::lambertian::Lambertian;
+
+    // Unit spheres centered at x = -0.5 and x = 0.5
+    fn csg(operation: CsgOperation) -> Csg<'static> {
+        let sphere = |x| {
+            Box::new(Sphere::new(
+                Point3::new(x, 0.0, 0.0),
+                1.0,
+                Box::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))),
+            ))
+        };
+        Csg::new(operation, sphere(-0.5), sphere(0.5))
+    }
+
+    // Entry and exit t of the spans of the x axis, walked from x = -5
+    fn spans(operation: CsgOperation) -> Vec<(f32, f32)> {
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        csg(operation)
+            .spans(&r)
+            .iter()
+            .map(|span| (span.entry.t(), span.exit.t()))
+            .collect()
+    }
+
+    fn assert_spans(actual: Vec<(f32, f32)>, expected: &[(f32, f32)]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a.0 - e.0).abs() < 1e-4 && (a.1 - e.1).abs() < 1e-4,
+                "{:?}",
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn union_spans_both_spheres() {
+        assert_spans(spans(CsgOperation::Union), &[(3.5, 6.5)]);
+    }
+
+    #[test]
+    fn intersection_spans_the_overlap() {
+        assert_spans(spans(CsgOperation::Intersection), &[(4.5, 5.5)]);
+    }
+
+    #[test]
+    fn difference_spans_the_left_sphere_outside_the_right_one() {
+        assert_spans(spans(CsgOperation::Difference), &[(3.5, 4.5)]);
+    }
+
+    #[test]
+    fn difference_faces_out_of_the_carved_surface() {
+        let difference = csg(CsgOperation::Difference);
+        let range = HitRange::new(0.001, f32::INFINITY);
+
+        // Leaving the solid through the carved surface, from inside of it
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let rec = difference
+            .hit(&r, HitRange::new(4.0, f32::INFINITY))
+            .unwrap();
+        assert!((rec.t() - 4.5).abs() < 1e-4);
+        assert!(!rec.front_face());
+
+        // Entering it through the carved surface, from the carved out space
+        let r = Ray::new(Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0), 0.0);
+        let rec = difference.hit(&r, range).unwrap();
+        assert!((rec.t() - 5.5).abs() < 1e-4);
+        assert!(rec.front_face());
+        assert!((*rec.normal() - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+    }
+}
@@ -0,0 +1,127 @@
+use std::f32::consts::PI;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::disk::disk_box;
+use crate::geometry::frame::Frame;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Angle around the local y axis mapped to [0, 1]
+pub(crate) fn azimuth(p: &Point3) -> f32 {
+    (p.z.atan2(p.x) + PI) / (2.0 * PI)
+}
+
+// Hit in the local frame of a shape: t, outward normal and uv
+pub(crate) type LocalHit = (f32, Vec3, (f32, f32));
+
+// Nearest candidate in range
+pub(crate) fn nearest(
+    candidates: impl IntoIterator<Item = Option<LocalHit>>,
+    range: &HitRange,
+) -> Option<LocalHit> {
+    candidates
+        .into_iter()
+        .flatten()
+        .filter(|(t, _, _)| range.contains(t))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+// Hit with the cap of radius r in the local plane y = height, facing along y
+// when up is set
+pub(crate) fn hit_cap(local: &Ray, height: f32, r: f32, up: bool) -> Option<LocalHit> {
+    if local.dir.y == 0.0 || r <= 0.0 {
+        return Option::None;
+    }
+
+    let t = (height - local.orig.y) / local.dir.y;
+    let p = local.at(t);
+    let distance = (p.x * p.x + p.z * p.z).sqrt();
+    if distance > r {
+        return Option::None;
+    }
+
+    let normal = Vec3::new(0.0, if up { 1.0 } else { -1.0 }, 0.0);
+    Option::Some((t, normal, (azimuth(&p), distance / r)))
+}
+
+// Capped cylinder between the centers of its two end disks
+pub struct Cylinder {
+    frame: Frame,
+    height: f32,
+    pub radius: f32,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Cylinder {
+    pub fn new(base: Point3, top: Point3, r: f32, m: Box<dyn Material + Send + Sync>) -> Self {
+        Cylinder {
+            frame: Frame::new(base, &(top - base)),
+            height: (top - base).length(),
+            radius: r.abs(),
+            material: m,
+        }
+    }
+}
+
+// Hit with the infinite tube of radius r around the local y axis, limited to
+// 0 <= y <= height, with v following y
+pub(crate) fn hit_tube(local: &Ray, height: f32, r: f32) -> [Option<LocalHit>; 2] {
+    let (o, d) = (&local.orig, &local.dir);
+    let a = d.x * d.x + d.z * d.z;
+    let half_b = o.x * d.x + o.z * d.z;
+    let c = o.x * o.x + o.z * o.z - r * r;
+
+    let discriminant = half_b * half_b - a * c;
+    if a == 0.0 || discriminant < 0.0 {
+        return [Option::None, Option::None];
+    }
+
+    let sqrtd = discriminant.sqrt();
+    [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a].map(|t| {
+        let p = local.at(t);
+        if (0.0..=height).contains(&p.y) {
+            Option::Some((
+                t,
+                Vec3::new(p.x / r, 0.0, p.z / r),
+                (azimuth(&p), p.y / height),
+            ))
+        } else {
+            Option::None
+        }
+    })
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local_ray(r);
+        let [side0, side1] = hit_tube(&local, self.height, self.radius);
+        let (t, normal, uv) = nearest(
+            [
+                side0,
+                side1,
+                hit_cap(&local, 0.0, self.radius, false),
+                hit_cap(&local, self.height, self.radius, true),
+            ],
+            &range,
+        )?;
+
+        Option::Some(HitRecord::new(
+            r.at(t),
+            self.material.as_ref(),
+            &self.frame.to_world_vector(&normal),
+            t,
+            uv,
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let top = self.frame.origin + self.height * self.frame.axis;
+        Option::Some(Aabb::surrounding_box(
+            &disk_box(&self.frame.origin, &self.frame.axis, self.radius),
+            &disk_box(&top, &self.frame.axis, self.radius),
+        ))
+    }
+}
@@ -0,0 +1,74 @@
+use std::f32::consts::PI;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::plane::{intersect_plane, tangent_basis};
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// The extent along each axis shrinks as the unit normal turns towards it
+pub(crate) fn disk_box(center: &Point3, normal: &Vec3, radius: f32) -> Aabb {
+    let n = normal;
+    let extent = radius
+        * Vec3::new(
+            (1.0 - n.x * n.x).max(0.0).sqrt(),
+            (1.0 - n.y * n.y).max(0.0).sqrt(),
+            (1.0 - n.z * n.z).max(0.0).sqrt(),
+        );
+    Aabb::new(center - extent, center + extent)
+}
+
+pub struct Disk {
+    pub center: Point3,
+    pub normal: Vec3,
+    pub radius: f32,
+    u_axis: Vec3,
+    v_axis: Vec3,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, r: f32, m: Box<dyn Material + Send + Sync>) -> Self {
+        let normal = Vec3::unit_vector(&normal);
+        let (u_axis, v_axis) = tangent_basis(&normal);
+
+        Disk {
+            center,
+            normal,
+            radius: r.abs(),
+            u_axis,
+            v_axis,
+            material: m,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let d = Vec3::dot(&self.normal, &self.center);
+        let t = intersect_plane(r, &self.normal, d, &range)?;
+
+        let p = r.at(t);
+        let offset = p - self.center;
+        let distance = offset.length();
+        if distance > self.radius {
+            return Option::None;
+        }
+
+        // u follows the angle around the normal, v the distance to the center
+        let phi = Vec3::dot(&offset, &self.v_axis).atan2(Vec3::dot(&offset, &self.u_axis));
+        Option::Some(HitRecord::new(
+            p,
+            self.material.as_ref(),
+            &self.normal,
+            t,
+            ((phi + PI) / (2.0 * PI), distance / self.radius),
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::Some(disk_box(&self.center, &self.normal, self.radius))
+    }
+}
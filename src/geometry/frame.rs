@@ -0,0 +1,45 @@
+use crate::geometry::plane::tangent_basis;
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+
+// Orthonormal frame whose y axis follows the axis of a shape of revolution.
+// Rays keep their parameterisation when moved in and out of it.
+pub struct Frame {
+    pub origin: Point3,
+    pub u: Vec3,
+    pub axis: Vec3,
+    pub v: Vec3,
+}
+
+impl Frame {
+    pub fn new(origin: Point3, axis: &Vec3) -> Self {
+        let axis = Vec3::unit_vector(axis);
+        let (v, u) = tangent_basis(&axis);
+
+        Frame { origin, u, axis, v }
+    }
+
+    pub fn to_local_vector(&self, v: &Vec3) -> Vec3 {
+        Vec3::new(
+            Vec3::dot(v, &self.u),
+            Vec3::dot(v, &self.axis),
+            Vec3::dot(v, &self.v),
+        )
+    }
+
+    pub fn to_local_point(&self, p: &Point3) -> Point3 {
+        self.to_local_vector(&(p - self.origin))
+    }
+
+    pub fn to_local_ray(&self, r: &Ray) -> Ray {
+        Ray {
+            orig: self.to_local_point(&r.orig),
+            dir: self.to_local_vector(&r.dir),
+            ..*r
+        }
+    }
+
+    pub fn to_world_vector(&self, v: &Vec3) -> Vec3 {
+        v.x * self.u + v.y * self.axis + v.z * self.v
+    }
+}
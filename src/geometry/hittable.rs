@@ -1,7 +1,10 @@
 use rand::Rng;
 use std::ops::{Deref, DerefMut, RangeInclusive};
+use std::sync::Arc;
 
 use crate::color::Color;
+use crate::geometry::aabb::Aabb;
+use crate::geometry::plane::Plane;
 use crate::geometry::ray::Ray;
 use crate::geometry::sphere::Sphere;
 use crate::geometry::{Point3, Vec3};
@@ -9,12 +12,14 @@ use crate::material::dielectric::Dielectric;
 use crate::material::lambertian::Lambertian;
 use crate::material::metal::Metal;
 use crate::material::Material;
+use crate::sampler::Sampler;
 
 pub struct HitRecord<'a> {
     p: Point3,
     normal: Vec3,
     material: &'a (dyn Material + Send + Sync),
     t: f32,
+    uv: (f32, f32),
     front_face: bool,
 }
 
@@ -24,6 +29,7 @@ impl<'a> HitRecord<'a> {
         material: &'a (dyn Material + Send + Sync),
         outward_normal: &Vec3,
         t: f32,
+        uv: (f32, f32),
         r: &Ray,
     ) -> Self {
         let front_face = Vec3::dot(&r.dir, outward_normal).is_sign_negative();
@@ -40,10 +46,40 @@ impl<'a> HitRecord<'a> {
             normal,
             material,
             t,
+            uv,
             front_face,
         }
     }
 
+    // Replaces the geometric normal by an interpolated one, the side of the
+    // surface is still given by the geometric normal
+    pub fn set_shading_normal(&mut self, outward_normal: &Vec3) {
+        self.normal = if self.front_face {
+            *outward_normal
+        } else {
+            -outward_normal
+        };
+    }
+
+    // Moves a hit found along a transformed copy of r back onto r, the normal
+    // must face the same side of the surface as before
+    pub fn transformed(self, r: &Ray, normal: Vec3) -> Self {
+        HitRecord {
+            p: r.at(self.t),
+            normal,
+            ..self
+        }
+    }
+
+    // The same hit seen from the other side of the surface, for solids
+    // carved out of others
+    pub fn flipped(self) -> Self {
+        HitRecord {
+            front_face: !self.front_face,
+            ..self
+        }
+    }
+
     pub fn normal(&self) -> &Vec3 {
         &self.normal
     }
@@ -56,33 +92,111 @@ impl<'a> HitRecord<'a> {
     pub fn front_face(&self) -> bool {
         self.front_face
     }
+    pub fn t(&self) -> f32 {
+        self.t
+    }
+    // Surface (u, v) texture coordinates
+    pub fn uv(&self) -> (f32, f32) {
+        self.uv
+    }
 }
 
 pub type HitRange = RangeInclusive<f32>;
 
+// Part of a ray inside a solid, between the surface hits where it enters
+// and leaves it
+pub struct Span<'a> {
+    pub entry: HitRecord<'a>,
+    pub exit: HitRecord<'a>,
+}
+
+// Give up on surfaces crossing a ray more often than this
+const MAX_CROSSINGS: usize = 64;
+
 pub trait Hittable {
-    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord>;
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>>;
+
+    // None for objects without finite extent, which cannot be stored in a BVH
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    // Every span of the whole line carrying r inside the object, sorted along
+    // the ray. This only makes sense for closed surfaces, the default walks
+    // through all the surface crossings.
+    fn spans(&self, r: &Ray) -> Vec<Span<'_>> {
+        let mut spans = vec![];
+        let mut entry: Option<HitRecord> = Option::None;
+        let mut start = f32::NEG_INFINITY;
+
+        for _ in 0..MAX_CROSSINGS {
+            let rec = match self.hit(r, HitRange::new(start, f32::INFINITY)) {
+                Some(rec) => rec,
+                None => break,
+            };
+            start = rec.t + 1e-4f32.max(rec.t.abs() * 1e-5);
+
+            // Exits without a matching entry come from open surfaces
+            if rec.front_face {
+                entry = Option::Some(rec);
+            } else if let Some(entry) = entry.take() {
+                spans.push(Span { entry, exit: rec });
+            }
+        }
+
+        spans
+    }
+
+    // Density over solid angle of the directions random_direction draws from
+    // the origin, only the objects usable as lights implement both
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    fn random_direction(&self, _origin: &Point3, _sampler: &mut dyn Sampler) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+}
+
+// Lets geometry shared between instances be stored like any other object
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        self.as_ref().hit(r, range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.as_ref().bounding_box()
+    }
+
+    fn spans(&self, r: &Ray) -> Vec<Span<'_>> {
+        self.as_ref().spans(r)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        self.as_ref().pdf_value(origin, direction)
+    }
+
+    fn random_direction(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        self.as_ref().random_direction(origin, sampler)
+    }
 }
 
 pub struct HittableList<'a>(Vec<Box<dyn Hittable + Sync + Send + 'a>>);
 
 impl HittableList<'_> {
     pub fn new() -> Self {
-        HittableList { 0: vec![] }
+        HittableList(vec![])
     }
 
-    pub fn random_scene() -> Self {
+    pub fn random_scene(rng: &mut impl Rng) -> Self {
         let mut world = Self::new();
         let material_ground = Box::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
 
-        let sphere_ground = Box::new(Sphere::new(
-            Point3::new(0.0, -1000.0, -1.0),
-            1000.0,
+        let plane_ground = Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
             material_ground,
         ));
-        (*world).push(sphere_ground);
+        (*world).push(plane_ground);
 
-        let mut rng = rand::thread_rng();
         for a in -11..11 {
             for b in -11..11 {
                 let choose_mat = rng.gen::<f32>();
@@ -95,13 +209,13 @@ impl HittableList<'_> {
                 if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
                     if choose_mat < 0.8 {
                         // diffuse
-                        let albedo = Color::random() * Color::random();
+                        let albedo = Color::random(rng) * Color::random(rng);
                         let sphere_material = Box::new(Lambertian::new(&albedo));
                         let sphere = Box::new(Sphere::new(center, 0.2, sphere_material));
                         (*world).push(sphere);
                     } else if choose_mat < 0.95 {
                         // metal
-                        let albedo = Color::random_bounded(0.5, 1.0);
+                        let albedo = Color::random_bounded(rng, 0.5, 1.0);
                         let fuzz = rng.gen_range(0.0f32, 0.5f32);
                         let sphere_material = Box::new(Metal::new(&albedo, fuzz));
                         let sphere = Box::new(Sphere::new(center, 0.2, sphere_material));
@@ -131,6 +245,12 @@ impl HittableList<'_> {
     }
 }
 
+impl Default for HittableList<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> Deref for HittableList<'a> {
     type Target = Vec<Box<dyn Hittable + Sync + Send + 'a>>;
 
@@ -146,7 +266,7 @@ impl DerefMut for HittableList<'_> {
 }
 
 impl Hittable for HittableList<'_> {
-    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord> {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
         let mut closest_so_far: f32 = *range.end();
         let mut hit_record: Option<HitRecord> = Option::None;
 
@@ -160,4 +280,34 @@ impl Hittable for HittableList<'_> {
 
         hit_record
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut boxes = self.0.iter().map(|hittable| hittable.bounding_box());
+        let first = boxes.next()??;
+
+        boxes.try_fold(first, |acc, bbox| Some(Aabb::surrounding_box(&acc, &bbox?)))
+    }
+
+    // Every object is picked with the same probability
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        if self.0.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f32 = self
+            .0
+            .iter()
+            .map(|hittable| hittable.pdf_value(origin, direction))
+            .sum();
+        sum / self.0.len() as f32
+    }
+
+    fn random_direction(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        if self.0.is_empty() {
+            return Vec3::new(1.0, 0.0, 0.0);
+        }
+
+        let index = ((sampler.get_1d() * self.0.len() as f32) as usize).min(self.0.len() - 1);
+        self.0[index].random_direction(origin, sampler)
+    }
 }
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::matrix::Matrix4;
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+
+// Places shared geometry in the world through an affine transform
+pub struct Instance<'a> {
+    object: Arc<dyn Hittable + Send + Sync + 'a>,
+    to_object: Matrix4,
+    // Normals are transformed by the inverse transpose
+    normal_to_world: Matrix4,
+    bbox: Option<Aabb>,
+}
+
+impl<'a> Instance<'a> {
+    pub fn new(object: Arc<dyn Hittable + Send + Sync + 'a>, to_world: Matrix4) -> Self {
+        let to_object = to_world
+            .inverse()
+            .expect("an instance transform must be invertible");
+
+        // Bound the transformed corners of the object box. Written as a
+        // plain loop, rustc 1.95 miscompiles the same fold over an iterator
+        // of boxes at opt-level 2 and above.
+        let bbox = object.bounding_box().map(|bbox| {
+            let mut min = Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+            let mut max = -min;
+            for i in 0..8 {
+                let corner = Point3::new(
+                    if i & 1 == 0 { bbox.min.x } else { bbox.max.x },
+                    if i & 2 == 0 { bbox.min.y } else { bbox.max.y },
+                    if i & 4 == 0 { bbox.min.z } else { bbox.max.z },
+                );
+                let p = to_world.transform_point(&corner);
+                min = Vec3::min(&min, &p);
+                max = Vec3::max(&max, &p);
+            }
+            Aabb::new(min, max)
+        });
+
+        Self {
+            object,
+            to_object,
+            normal_to_world: to_object.transpose(),
+            bbox,
+        }
+    }
+}
+
+impl Hittable for Instance<'_> {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        // The direction is not normalized so that t is the same in both spaces
+        let object_ray = Ray {
+            orig: self.to_object.transform_point(&r.orig),
+            dir: self.to_object.transform_vector(&r.dir),
+            ..*r
+        };
+
+        self.object.hit(&object_ray, range).map(|rec| {
+            let normal = Vec3::unit_vector(&self.normal_to_world.transform_vector(rec.normal()));
+            rec.transformed(r, normal)
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::geometry::box_shape::BoxShape;
+    use crate::geometry::sphere::Sphere;
+    use crate::material::lambertian::Lambertian;
+    use crate::material::Material;
+
+    fn white() -> Box<dyn Material + Send + Sync> {
+        Box::new(Lambertian::new(&Color::new(0.73, 0.73, 0.73)))
+    }
+
+    #[test]
+    fn rotated_box_bounds_its_corners() {
+        let size = Vec3::new(165.0, 165.0, 165.0);
+        let to_world = Matrix4::translation(&Vec3::new(130.0, 0.0, 65.0))
+            * Matrix4::rotation(&Vec3::new(0.0, 1.0, 0.0), -18.0);
+        let object = Arc::new(BoxShape::new(Point3::new(0.0, 0.0, 0.0), size, white));
+        let bbox = Instance::new(object, to_world).bounding_box().unwrap();
+
+        for i in 0..8 {
+            let corner = Point3::new(
+                if i & 1 == 0 { 0.0 } else { size.x },
+                if i & 2 == 0 { 0.0 } else { size.y },
+                if i & 4 == 0 { 0.0 } else { size.z },
+            );
+            let p = to_world.transform_point(&corner);
+            for axis in 0..3 {
+                assert!(bbox.min[axis] <= p[axis] && p[axis] <= bbox.max[axis]);
+            }
+        }
+    }
+
+    #[test]
+    fn hits_scaled_sphere_with_transformed_normal() {
+        let sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, white());
+        let to_world = Matrix4::translation(&Vec3::new(0.0, 0.0, -5.0))
+            * Matrix4::scaling(&Vec3::new(2.0, 1.0, 1.0));
+        let instance = Instance::new(Arc::new(sphere), to_world);
+
+        // The object ray is not normalized, t is the same in both spaces
+        let r = Ray::new(Point3::new(5.0, 0.0, -5.0), Vec3::new(-1.0, 0.0, 0.0), 0.0);
+        let rec = instance
+            .hit(&r, HitRange::new(0.001, f32::INFINITY))
+            .unwrap();
+        assert!((rec.t() - 3.0).abs() < 1e-5);
+        assert!((*rec.normal() - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+
+        // Normals of a stretched sphere lean towards the short axis
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let p = to_world.transform_point(&Point3::new(half, half, 0.0));
+        let normal = Vec3::unit_vector(&Vec3::new(0.5, 1.0, 0.0));
+        let r = Ray::new(p + 2.0 * normal, -normal, 0.0);
+        let rec = instance
+            .hit(&r, HitRange::new(0.001, f32::INFINITY))
+            .unwrap();
+        assert!((*rec.p() - p).length() < 1e-4);
+        assert!((*rec.normal() - normal).length() < 1e-4);
+    }
+
+    #[test]
+    fn hits_rotated_box() {
+        let cube = BoxShape::new(
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            white,
+        );
+        let to_world = Matrix4::rotation(&Vec3::new(0.0, 1.0, 0.0), 45.0);
+        let instance = Instance::new(Arc::new(cube), to_world);
+
+        // The +z face now faces +x and +z
+        let r = Ray::new(Point3::new(5.0, 0.0, 0.5), Vec3::new(-1.0, 0.0, 0.0), 0.0);
+        let rec = instance
+            .hit(&r, HitRange::new(0.001, f32::INFINITY))
+            .unwrap();
+        let x = std::f32::consts::SQRT_2 - 0.5;
+        assert!((rec.t() - (5.0 - x)).abs() < 1e-4);
+        let normal = Vec3::unit_vector(&Vec3::new(1.0, 0.0, 1.0));
+        assert!((*rec.normal() - normal).length() < 1e-5);
+
+        // Missed where the unrotated box would have been hit
+        let r = Ray::new(Point3::new(5.0, 0.0, 1.3), Vec3::new(-1.0, 0.0, 0.0), 0.0);
+        assert!(instance.hit(&r, HitRange::new(0.001, 4.5)).is_none());
+    }
+}
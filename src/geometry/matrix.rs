@@ -0,0 +1,197 @@
+use std::ops::Mul;
+
+use crate::geometry::{Point3, Vec3};
+
+// Row-major affine transform, the last row is always (0, 0, 0, 1)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const fn identity() -> Self {
+        Self {
+            m: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn translation(offset: &Vec3) -> Self {
+        let mut m = Self::identity();
+        m.m[0][3] = offset.x;
+        m.m[1][3] = offset.y;
+        m.m[2][3] = offset.z;
+        m
+    }
+
+    pub fn scaling(factors: &Vec3) -> Self {
+        let mut m = Self::identity();
+        m.m[0][0] = factors.x;
+        m.m[1][1] = factors.y;
+        m.m[2][2] = factors.z;
+        m
+    }
+
+    // Counter-clockwise rotation about the axis, looking down from its tip
+    pub fn rotation(axis: &Vec3, angle_deg: f32) -> Self {
+        let a = Vec3::unit_vector(axis);
+        let (sin, cos) = angle_deg.to_radians().sin_cos();
+        let k = 1.0 - cos;
+
+        Self {
+            m: [
+                [
+                    cos + a.x * a.x * k,
+                    a.x * a.y * k - a.z * sin,
+                    a.x * a.z * k + a.y * sin,
+                    0.0,
+                ],
+                [
+                    a.y * a.x * k + a.z * sin,
+                    cos + a.y * a.y * k,
+                    a.y * a.z * k - a.x * sin,
+                    0.0,
+                ],
+                [
+                    a.z * a.x * k - a.y * sin,
+                    a.z * a.y * k + a.x * sin,
+                    cos + a.z * a.z * k,
+                    0.0,
+                ],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::identity();
+        for (i, row) in self.m.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                t.m[j][i] = *value;
+            }
+        }
+        t
+    }
+
+    // None when the transform collapses space, e.g. a scale by zero
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.m;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+
+        // Adjugate of the linear part
+        let adj = [
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ];
+        let det = m[0][0] * adj[0][0] + m[0][1] * adj[1][0] + m[0][2] * adj[2][0];
+        if det.abs() < f32::EPSILON * f32::EPSILON {
+            return Option::None;
+        }
+
+        let mut inv = Self::identity();
+        for (row, adj_row) in inv.m.iter_mut().zip(&adj) {
+            for (value, cofactor) in row.iter_mut().zip(adj_row) {
+                *value = cofactor / det;
+            }
+            row[3] = -(0..3).map(|j| row[j] * m[j][3]).sum::<f32>();
+        }
+        Option::Some(inv)
+    }
+
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        self.transform_vector(p) + Vec3::new(self.m[0][3], self.m[1][3], self.m[2][3])
+    }
+
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+
+    // The right hand side is applied first
+    fn mul(self, rhs: Self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Self { m }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Matrix4, b: &Matrix4) {
+        for (row_a, row_b) in a.m.iter().zip(&b.m) {
+            for (x, y) in row_a.iter().zip(row_b) {
+                assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let m = Matrix4::translation(&Vec3::new(1.0, -2.0, 3.0))
+            * Matrix4::rotation(&Vec3::new(1.0, 2.0, -1.0), 37.0)
+            * Matrix4::scaling(&Vec3::new(2.0, 0.5, 3.0));
+        let inverse = m.inverse().unwrap();
+        assert_close(&(m * inverse), &Matrix4::identity());
+        assert_close(&(inverse * m), &Matrix4::identity());
+    }
+
+    #[test]
+    fn collapsing_transform_has_no_inverse() {
+        assert!(Matrix4::scaling(&Vec3::new(1.0, 0.0, 1.0))
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn rotation_is_counter_clockwise() {
+        let m = Matrix4::rotation(&Vec3::new(0.0, 0.0, 2.0), 90.0);
+        let v = m.transform_vector(&Vec3::new(1.0, 0.0, 0.0));
+        assert!((v - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-6);
+
+        // Points are translated, vectors are not
+        let m = Matrix4::translation(&Vec3::new(1.0, 2.0, 3.0));
+        let p = m.transform_point(&Point3::new(1.0, 1.0, 1.0));
+        assert_eq!(p, Point3::new(2.0, 3.0, 4.0));
+        let v = m.transform_vector(&Vec3::new(1.0, 1.0, 1.0));
+        assert_eq!(v, Vec3::new(1.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::bvh::Bvh;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable, HittableList};
+use crate::geometry::ray::Ray;
+use crate::geometry::triangle;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Vertex attributes are indexed together, normals and uvs are either empty
+// or as long as positions
+struct MeshData {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f32, f32)>,
+    faces: Vec<[usize; 3]>,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl MeshData {
+    fn vertices(&self, face: usize) -> [&Point3; 3] {
+        let [i0, i1, i2] = self.faces[face];
+        [
+            &self.positions[i0],
+            &self.positions[i1],
+            &self.positions[i2],
+        ]
+    }
+}
+
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    face: usize,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let vertices = self.mesh.vertices(self.face);
+        let (t, barycentrics) = triangle::intersect(r, vertices, &range)?;
+
+        let face = &self.mesh.faces[self.face];
+        let uv = if self.mesh.uvs.is_empty() {
+            (barycentrics[1], barycentrics[2])
+        } else {
+            (0..3).fold((0.0, 0.0), |acc, i| {
+                let (u, v) = self.mesh.uvs[face[i]];
+                (acc.0 + barycentrics[i] * u, acc.1 + barycentrics[i] * v)
+            })
+        };
+
+        let geometric_normal = triangle::geometric_normal(vertices);
+        let mut rec = HitRecord::new(
+            r.at(t),
+            self.mesh.material.as_ref(),
+            &geometric_normal,
+            t,
+            uv,
+            r,
+        );
+
+        if !self.mesh.normals.is_empty() {
+            let interpolated = (0..3).fold(Vec3::default(), |acc, i| {
+                acc + barycentrics[i] * self.mesh.normals[face[i]]
+            });
+
+            // Keep the shading normal on the same side as the actual surface
+            if !interpolated.near_zero() {
+                let shading_normal = Vec3::unit_vector(&interpolated);
+                if Vec3::dot(&shading_normal, &geometric_normal) < 0.0 {
+                    rec.set_shading_normal(&-shading_normal);
+                } else {
+                    rec.set_shading_normal(&shading_normal);
+                }
+            }
+        }
+
+        Option::Some(rec)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::Some(triangle::bounding_box(self.mesh.vertices(self.face)))
+    }
+}
+
+pub struct TriangleMesh {
+    bvh: Bvh<'static>,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<Point3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<(f32, f32)>,
+        faces: Vec<[usize; 3]>,
+        material: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        assert!(
+            normals.is_empty() || normals.len() == positions.len(),
+            "Mesh normals do not match positions!"
+        );
+        assert!(
+            uvs.is_empty() || uvs.len() == positions.len(),
+            "Mesh uvs do not match positions!"
+        );
+        assert!(
+            faces.iter().flatten().all(|&i| i < positions.len()),
+            "Mesh face index out of bounds!"
+        );
+
+        let mesh = Arc::new(MeshData {
+            positions,
+            normals,
+            uvs,
+            faces,
+            material,
+        });
+
+        // Degenerate faces can never be hit
+        let mut triangles = HittableList::new();
+        (0..mesh.faces.len())
+            .filter(|&face| {
+                let [v0, v1, v2] = mesh.vertices(face);
+                Vec3::cross(&(v1 - v0), &(v2 - v0)).length_squared() > 0.0
+            })
+            .for_each(|face| {
+                (*triangles).push(Box::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    face,
+                }))
+            });
+
+        Self {
+            bvh: Bvh::new(triangles),
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        self.bvh.hit(r, range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bvh.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::material::lambertian::Lambertian;
+
+    fn mesh(normals: Vec<Vec3>, uvs: Vec<(f32, f32)>) -> TriangleMesh {
+        TriangleMesh::new(
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            normals,
+            uvs,
+            vec![[0, 1, 2]],
+            Box::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    // Hit at barycentric weights (0.5, 0.25, 0.25), seen from above or below
+    fn hit(mesh: &TriangleMesh, dz: f32) -> HitRecord<'_> {
+        let r = Ray::new(Point3::new(0.25, 0.25, -dz), Vec3::new(0.0, 0.0, dz), 0.0);
+        mesh.hit(&r, HitRange::new(0.001, f32::INFINITY)).unwrap()
+    }
+
+    #[test]
+    fn interpolates_vertex_normals() {
+        let mesh = mesh(
+            vec![
+                Vec3::new(0.0, 0.0, 1.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ],
+            vec![],
+        );
+        let expected = Vec3::unit_vector(&Vec3::new(0.25, 0.25, 0.5));
+        assert!((*hit(&mesh, -1.0).normal() - expected).length() < 1e-6);
+
+        // From behind the shading normal still faces the ray
+        let rec = hit(&mesh, 1.0);
+        assert!(!rec.front_face());
+        assert!((*rec.normal() + expected).length() < 1e-6);
+    }
+
+    #[test]
+    fn interpolates_vertex_uvs() {
+        let textured = mesh(vec![], vec![(0.5, 0.5), (1.0, 0.5), (0.5, 1.0)]);
+        let (u, v) = hit(&textured, -1.0).uv();
+        assert!((u - 0.625).abs() < 1e-6 && (v - 0.625).abs() < 1e-6);
+
+        // Barycentric coordinates without uvs
+        let (u, v) = hit(&mesh(vec![], vec![]), -1.0).uv();
+        assert!((u - 0.25).abs() < 1e-6 && (v - 0.25).abs() < 1e-6);
+    }
+}
@@ -1,38 +1,69 @@
-use std::ops::{Add, Div, Mul, Neg, Sub};
+//! Vectors, rays, the shapes of a scene and the structures accelerating
+//! their intersection.
+//!
+//! Shapes implement [`hittable::Hittable`] and get their own material. They
+//! are gathered in a [`hittable::HittableList`], which is a world in itself.
+
+use std::ops::{Add, Div, Index, Mul, Neg, Sub};
 
 use rand::Rng;
 
+pub mod aabb;
+pub mod box_shape;
+pub mod bvh;
 pub mod camera;
+pub mod capsule;
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod cylinder;
+pub mod disk;
+pub(crate) mod frame;
 pub mod hittable;
+pub mod instance;
+pub mod matrix;
+pub mod mesh;
+pub mod moving_sphere;
+pub mod plane;
+pub mod quad;
 pub mod ray;
+pub mod rect;
+pub(crate) mod solver;
 pub mod sphere;
+pub mod torus;
+pub mod triangle;
 
+/// Three components, used for points, directions and colors.
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Vec3 {
+    /// First component, the red one of colors.
     pub x: f32,
+    /// Second component, the green one of colors.
     pub y: f32,
+    /// Third component, the blue one of colors.
     pub z: f32,
 }
 
 impl Vec3 {
+    /// Vector of the given components.
     pub const fn new(x: f32, y: f32, z: f32) -> Self {
         Self { x, y, z }
     }
 
-    pub fn length(&self) -> f32 {
+    pub(crate) fn length(&self) -> f32 {
         self.length_squared().sqrt()
     }
 
-    pub fn length_squared(&self) -> f32 {
+    pub(crate) fn length_squared(&self) -> f32 {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
 
-    pub fn near_zero(&self) -> bool {
+    pub(crate) fn near_zero(&self) -> bool {
         let s = 1e-8f32;
         self.x.abs() < s && self.y.abs() < s && self.z.abs() < s
     }
 
-    pub fn sqrt(&self) -> Self {
+    pub(crate) fn sqrt(&self) -> Self {
         Self {
             x: self.x.sqrt(),
             y: self.y.sqrt(),
@@ -40,15 +71,15 @@ impl Vec3 {
         }
     }
 
-    pub fn unit_vector(v: &Self) -> Self {
+    pub(crate) fn unit_vector(v: &Self) -> Self {
         v / v.length()
     }
 
-    pub fn dot(u: &Self, v: &Self) -> f32 {
+    pub(crate) fn dot(u: &Self, v: &Self) -> f32 {
         u.x * v.x + u.y * v.y + u.z * v.z
     }
 
-    pub fn cross(u: &Self, v: &Self) -> Self {
+    pub(crate) fn cross(u: &Self, v: &Self) -> Self {
         Self::new(
             u.y * v.z - u.z * v.y,
             u.z * v.x - u.x * v.z,
@@ -56,11 +87,19 @@ impl Vec3 {
         )
     }
 
-    pub fn reflect(v: &Self, n: &Self) -> Self {
+    pub(crate) fn min(u: &Self, v: &Self) -> Self {
+        Self::new(u.x.min(v.x), u.y.min(v.y), u.z.min(v.z))
+    }
+
+    pub(crate) fn max(u: &Self, v: &Self) -> Self {
+        Self::new(u.x.max(v.x), u.y.max(v.y), u.z.max(v.z))
+    }
+
+    pub(crate) fn reflect(v: &Self, n: &Self) -> Self {
         v - 2.0 * Self::dot(v, n) * n
     }
 
-    pub fn refract(uv: &Self, n: &Self, etai_over_etat: f32) -> Self {
+    pub(crate) fn refract(uv: &Self, n: &Self, etai_over_etat: f32) -> Self {
         let cos_theta = Self::dot(&-uv, n).min(1.0);
         let r_out_perp = etai_over_etat * (uv + cos_theta * n);
         let r_out_parallel = -(1.0 - r_out_perp.length_squared()).abs().sqrt() * n;
@@ -68,8 +107,7 @@ impl Vec3 {
         r_out_perp + r_out_parallel
     }
 
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+    pub(crate) fn random(rng: &mut impl Rng) -> Self {
         Self {
             x: rng.gen::<f32>(),
             y: rng.gen::<f32>(),
@@ -77,46 +115,13 @@ impl Vec3 {
         }
     }
 
-    pub fn random_bounded(min: f32, max: f32) -> Self {
-        let mut rng = rand::thread_rng();
+    pub(crate) fn random_bounded(rng: &mut impl Rng, min: f32, max: f32) -> Self {
         Self {
             x: rng.gen_range(min, max),
             y: rng.gen_range(min, max),
             z: rng.gen_range(min, max),
         }
     }
-
-    pub fn random_in_unit_disk() -> Self {
-        let mut rng = rand::thread_rng();
-        loop {
-            let p = Self::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0), 0.0);
-            if p.length_squared() < 1.0 {
-                break p;
-            }
-        }
-    }
-
-    pub fn random_in_unit_sphere() -> Self {
-        loop {
-            let p = Vec3::random_bounded(-1.0, 1.0);
-            if p.length_squared() < 1.0 {
-                break p;
-            }
-        }
-    }
-
-    pub fn random_unit_vector() -> Self {
-        Self::unit_vector(&Self::random_in_unit_sphere())
-    }
-
-    pub fn random_in_hemisphere(normal: &Vec3) -> Self {
-        let in_unit_sphere = Self::random_in_unit_sphere();
-        if Vec3::dot(&in_unit_sphere, normal) > 0.0 {
-            in_unit_sphere
-        } else {
-            -in_unit_sphere
-        }
-    }
 }
 
 impl Add for &Vec3 {
@@ -275,6 +280,44 @@ impl Neg for Vec3 {
     }
 }
 
+impl Index<usize> for Vec3 {
+    type Output = f32;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Invalid axis index {}!", axis),
+        }
+    }
+}
+
 //TODO Utiliser le pattern newtype pour distinguer Point3, Color et Vec3
 // sans dupliquer ou router manuellement les methodes (Dered?)
+/// A point in space.
 pub type Point3 = Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::hittable::{HitRange, Hittable, HittableList};
+    use super::ray::Ray;
+    use super::sphere::Sphere;
+    use super::*;
+    use crate::color::Color;
+    use crate::material::lambertian::Lambertian;
+
+    #[test]
+    fn list_hits_sphere() {
+        let mut world = HittableList::new();
+        world.push(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Box::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))),
+        )));
+
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let rec = world.hit(&r, HitRange::new(0.001, f32::INFINITY)).unwrap();
+        assert_eq!(rec.t(), 0.5);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::sphere::{hit_sphere, sphere_box};
+use crate::geometry::Point3;
+use crate::material::Material;
+
+// The center follows a piecewise linear path through keyframes sorted by
+// time, and stays at the first or last keyframe outside of them
+pub struct MovingSphere {
+    keyframes: Vec<(f32, Point3)>,
+    radius: f32,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl MovingSphere {
+    pub fn linear(
+        center0: Point3,
+        time0: f32,
+        center1: Point3,
+        time1: f32,
+        r: f32,
+        m: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        Self::keyframed(vec![(time0, center0), (time1, center1)], r, m)
+    }
+
+    pub fn keyframed(
+        mut keyframes: Vec<(f32, Point3)>,
+        r: f32,
+        m: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        assert!(!keyframes.is_empty(), "a moving sphere needs a keyframe");
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        MovingSphere {
+            keyframes,
+            radius: r,
+            material: m,
+        }
+    }
+
+    pub fn center(&self, time: f32) -> Point3 {
+        let next = self.keyframes.partition_point(|(t, _)| *t <= time);
+        if next == 0 {
+            return self.keyframes[0].1;
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1].1;
+        }
+
+        let (time0, center0) = self.keyframes[next - 1];
+        let (time1, center1) = self.keyframes[next];
+        center0 + ((time - time0) / (time1 - time0)) * (center1 - center0)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        hit_sphere(
+            &self.center(r.time),
+            self.radius,
+            self.material.as_ref(),
+            r,
+            range,
+        )
+    }
+
+    // The path is piecewise linear so the keyframe boxes enclose all of it
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.keyframes
+            .iter()
+            .map(|(_, center)| sphere_box(center, self.radius))
+            .reduce(|box0, box1| Aabb::surrounding_box(&box0, &box1))
+    }
+}
@@ -0,0 +1,82 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Distance along r to the plane of points p such that dot(normal, p) = d
+pub(crate) fn intersect_plane(r: &Ray, normal: &Vec3, d: f32, range: &HitRange) -> Option<f32> {
+    let denom = Vec3::dot(normal, &r.dir);
+    if denom.abs() < 1e-8 {
+        return Option::None;
+    }
+
+    let t = (d - Vec3::dot(normal, &r.orig)) / denom;
+    if range.contains(&t) {
+        Option::Some(t)
+    } else {
+        Option::None
+    }
+}
+
+// Two unit vectors spanning the plane orthogonal to the unit normal
+pub(crate) fn tangent_basis(normal: &Vec3) -> (Vec3, Vec3) {
+    let a = if normal.x.abs() > 0.9 {
+        Vec3::new(0.0, 1.0, 0.0)
+    } else {
+        Vec3::new(1.0, 0.0, 0.0)
+    };
+    let u = Vec3::unit_vector(&Vec3::cross(&a, normal));
+    let v = Vec3::cross(normal, &u);
+    (u, v)
+}
+
+// Infinite plane, which has no bounding box and stays out of the BVH
+pub struct Plane {
+    pub point: Point3,
+    pub normal: Vec3,
+    u_axis: Vec3,
+    v_axis: Vec3,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, m: Box<dyn Material + Send + Sync>) -> Self {
+        let normal = Vec3::unit_vector(&normal);
+        let (u_axis, v_axis) = tangent_basis(&normal);
+
+        Plane {
+            point,
+            normal,
+            u_axis,
+            v_axis,
+            material: m,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let d = Vec3::dot(&self.normal, &self.point);
+        let t = intersect_plane(r, &self.normal, d, &range)?;
+
+        // The texture coordinates are distances along the plane axes
+        let p = r.at(t);
+        let offset = p - self.point;
+        Option::Some(HitRecord::new(
+            p,
+            self.material.as_ref(),
+            &self.normal,
+            t,
+            (
+                Vec3::dot(&offset, &self.u_axis),
+                Vec3::dot(&offset, &self.v_axis),
+            ),
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::None
+    }
+}
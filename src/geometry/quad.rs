@@ -0,0 +1,91 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::plane::intersect_plane;
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+use crate::sampler::Sampler;
+
+// Parallelogram spanned by u and v from the corner q, facing along u x v
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    normal: Vec3,
+    d: f32,
+    // Maps a point of the plane to its (u, v) coordinates
+    w: Vec3,
+    area: f32,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, m: Box<dyn Material + Send + Sync>) -> Self {
+        let n = Vec3::cross(&u, &v);
+        let normal = Vec3::unit_vector(&n);
+
+        Quad {
+            q,
+            u,
+            v,
+            normal,
+            d: Vec3::dot(&normal, &q),
+            w: n / Vec3::dot(&n, &n),
+            area: n.length(),
+            material: m,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let t = intersect_plane(r, &self.normal, self.d, &range)?;
+
+        let p = r.at(t);
+        let planar = p - self.q;
+        let alpha = Vec3::dot(&self.w, &Vec3::cross(&planar, &self.v));
+        let beta = Vec3::dot(&self.w, &Vec3::cross(&self.u, &planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return Option::None;
+        }
+
+        Option::Some(HitRecord::new(
+            p,
+            self.material.as_ref(),
+            &self.normal,
+            t,
+            (alpha, beta),
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let diagonal0 = Aabb::new(
+            Vec3::min(&self.q, &(self.q + self.u + self.v)),
+            Vec3::max(&self.q, &(self.q + self.u + self.v)),
+        );
+        let diagonal1 = Aabb::new(
+            Vec3::min(&(self.q + self.u), &(self.q + self.v)),
+            Vec3::max(&(self.q + self.u), &(self.q + self.v)),
+        );
+        Option::Some(Aabb::surrounding_box(&diagonal0, &diagonal1))
+    }
+
+    // Uniform over the area, converted to solid angle as seen from the origin
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let rec = match self.hit(&ray, HitRange::new(0.001, f32::INFINITY)) {
+            Some(rec) => rec,
+            None => return 0.0,
+        };
+
+        let distance_squared = rec.t() * rec.t() * direction.length_squared();
+        let cosine = Vec3::dot(direction, &self.normal).abs() / direction.length();
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random_direction(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        let (alpha, beta) = sampler.get_2d();
+        self.q + alpha * self.u + beta * self.v - origin
+    }
+}
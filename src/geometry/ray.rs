@@ -1,15 +1,30 @@
 use crate::geometry::Point3;
 use crate::geometry::Vec3;
+use crate::sampler::Sampler;
 
 #[derive(Default, Debug)]
 pub struct Ray {
     pub orig: Point3,
     pub dir: Vec3,
+    pub time: f32,
+    // Bits drawn from the sampler deciding where the ray scatters in
+    // participating media, hits do not get a sampler of their own
+    pub sample: u64,
 }
 
 impl Ray {
-    pub fn new(orig: Point3, dir: Vec3) -> Self {
-        Self { orig, dir }
+    pub fn new(orig: Point3, dir: Vec3, time: f32) -> Self {
+        Self {
+            orig,
+            dir,
+            time,
+            sample: 0,
+        }
+    }
+
+    pub fn with_sample(mut self, sampler: &mut dyn Sampler) -> Self {
+        self.sample = sampler.get_1d().to_bits() as u64;
+        self
     }
 
     pub fn at(&self, t: f32) -> Point3 {
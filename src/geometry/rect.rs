@@ -0,0 +1,115 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::Point3;
+use crate::material::Material;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RectPlane {
+    Xy,
+    Xz,
+    Yz,
+}
+
+impl RectPlane {
+    // The two axes spanning the rectangle, then the one along its normal
+    fn axes(self) -> (usize, usize, usize) {
+        match self {
+            RectPlane::Xy => (0, 1, 2),
+            RectPlane::Xz => (0, 2, 1),
+            RectPlane::Yz => (1, 2, 0),
+        }
+    }
+
+    fn point(self, a: f32, b: f32, k: f32) -> Point3 {
+        let (axis_a, axis_b, axis_k) = self.axes();
+        let mut coords = [0.0; 3];
+        coords[axis_a] = a;
+        coords[axis_b] = b;
+        coords[axis_k] = k;
+        Point3::new(coords[0], coords[1], coords[2])
+    }
+}
+
+// Rectangle in a plane orthogonal to a coordinate axis, facing the positive
+// direction of that axis
+pub struct AxisAlignedRect {
+    plane: RectPlane,
+    a0: f32,
+    a1: f32,
+    b0: f32,
+    b1: f32,
+    k: f32,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl AxisAlignedRect {
+    pub fn new(
+        plane: RectPlane,
+        (a0, a1): (f32, f32),
+        (b0, b1): (f32, f32),
+        k: f32,
+        m: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        AxisAlignedRect {
+            plane,
+            a0: a0.min(a1),
+            a1: a0.max(a1),
+            b0: b0.min(b1),
+            b1: b0.max(b1),
+            k,
+            material: m,
+        }
+    }
+
+    pub fn xy(x: (f32, f32), y: (f32, f32), z: f32, m: Box<dyn Material + Send + Sync>) -> Self {
+        Self::new(RectPlane::Xy, x, y, z, m)
+    }
+
+    pub fn xz(x: (f32, f32), z: (f32, f32), y: f32, m: Box<dyn Material + Send + Sync>) -> Self {
+        Self::new(RectPlane::Xz, x, z, y, m)
+    }
+
+    pub fn yz(y: (f32, f32), z: (f32, f32), x: f32, m: Box<dyn Material + Send + Sync>) -> Self {
+        Self::new(RectPlane::Yz, y, z, x, m)
+    }
+}
+
+impl Hittable for AxisAlignedRect {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let (axis_a, axis_b, axis_k) = self.plane.axes();
+        if r.dir[axis_k] == 0.0 {
+            return Option::None;
+        }
+
+        let t = (self.k - r.orig[axis_k]) / r.dir[axis_k];
+        if !range.contains(&t) {
+            return Option::None;
+        }
+
+        let p = r.at(t);
+        let (a, b) = (p[axis_a], p[axis_b]);
+        if a < self.a0 || a > self.a1 || b < self.b0 || b > self.b1 {
+            return Option::None;
+        }
+
+        Option::Some(HitRecord::new(
+            p,
+            self.material.as_ref(),
+            &self.plane.point(0.0, 0.0, 1.0),
+            t,
+            (
+                (a - self.a0) / (self.a1 - self.a0),
+                (b - self.b0) / (self.b1 - self.b0),
+            ),
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::Some(Aabb::new(
+            self.plane.point(self.a0, self.b0, self.k),
+            self.plane.point(self.a1, self.b1, self.k),
+        ))
+    }
+}
@@ -0,0 +1,218 @@
+use std::f64::consts::PI;
+
+// Real roots of low degree polynomials, after "Solving Quartics and Cubics
+// for Graphics" by Jochen Schwarze in Graphics Gems I. Coefficients go from
+// the highest degree down and the roots are not sorted.
+
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Roots {
+    values: [f64; 4],
+    len: usize,
+}
+
+impl Roots {
+    fn push(&mut self, root: f64) {
+        self.values[self.len] = root;
+        self.len += 1;
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values[..self.len]
+    }
+}
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+// x^2 + p x + q
+fn solve_normed_quadratic(p: f64, q: f64, roots: &mut Roots) {
+    let half_p = p / 2.0;
+    let d = half_p * half_p - q;
+
+    if is_zero(d) {
+        roots.push(-half_p);
+    } else if d > 0.0 {
+        let sqrt_d = d.sqrt();
+        roots.push(sqrt_d - half_p);
+        roots.push(-sqrt_d - half_p);
+    }
+}
+
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Roots {
+    let mut roots = Roots::default();
+    if is_zero(a) {
+        if !is_zero(b) {
+            roots.push(-c / b);
+        }
+    } else {
+        solve_normed_quadratic(b / a, c / a, &mut roots);
+    }
+    roots
+}
+
+// x^3 + a x^2 + b x + c
+fn solve_normed_cubic(a: f64, b: f64, c: f64) -> Roots {
+    let mut roots = Roots::default();
+
+    // Substitute x = y - a/3 to eliminate the quadric term: y^3 + p y + q
+    let sq_a = a * a;
+    let p = (-sq_a / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * sq_a - a * b / 3.0 + c) / 2.0;
+
+    // Cardano's formula
+    let cb_p = p * p * p;
+    let d = q * q + cb_p;
+
+    if is_zero(d) {
+        if is_zero(q) {
+            roots.push(0.0);
+        } else {
+            let u = (-q).cbrt();
+            roots.push(2.0 * u);
+            roots.push(-u);
+        }
+    } else if d < 0.0 {
+        // Casus irreducibilis: three real solutions
+        let phi = (-q / (-cb_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        roots.push(t * phi.cos());
+        roots.push(-t * (phi + PI / 3.0).cos());
+        roots.push(-t * (phi - PI / 3.0).cos());
+    } else {
+        let sqrt_d = d.sqrt();
+        roots.push((sqrt_d - q).cbrt() - (sqrt_d + q).cbrt());
+    }
+
+    for root in &mut roots.values[..roots.len] {
+        *root -= a / 3.0;
+    }
+    roots
+}
+
+pub fn solve_quartic(c: [f64; 5]) -> Roots {
+    if is_zero(c[0]) {
+        let mut roots = Roots::default();
+        if is_zero(c[1]) {
+            return solve_quadratic(c[2], c[3], c[4]);
+        }
+        for &root in solve_normed_cubic(c[2] / c[1], c[3] / c[1], c[4] / c[1]).as_slice() {
+            roots.push(root);
+        }
+        return roots;
+    }
+
+    // x^4 + a x^3 + b x^2 + c x + d
+    let (a, b, c, d) = (c[1] / c[0], c[2] / c[0], c[3] / c[0], c[4] / c[0]);
+
+    // Substitute x = y - a/4 to eliminate the cubic term: y^4 + p y^2 + q y + r
+    let sq_a = a * a;
+    let p = -3.0 / 8.0 * sq_a + b;
+    let q = sq_a * a / 8.0 - a * b / 2.0 + c;
+    let r = -3.0 / 256.0 * sq_a * sq_a + sq_a * b / 16.0 - a * c / 4.0 + d;
+
+    let mut roots = Roots::default();
+    if is_zero(r) {
+        // No absolute term: y (y^3 + p y + q) = 0
+        roots.push(0.0);
+        for &root in solve_normed_cubic(0.0, p, q).as_slice() {
+            roots.push(root);
+        }
+    } else {
+        // Solve the resolvent cubic and take its one real solution...
+        let z = solve_normed_cubic(-p / 2.0, -r, r * p / 2.0 - q * q / 8.0).as_slice()[0];
+
+        // ...to build two quadric equations
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return roots;
+        };
+
+        let v = if q < 0.0 { -v } else { v };
+        solve_normed_quadratic(v, z - u, &mut roots);
+        solve_normed_quadratic(-v, z + u, &mut roots);
+    }
+
+    for root in &mut roots.values[..roots.len] {
+        *root -= a / 4.0;
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every root is one of the expected ones and every expected one is found
+    fn assert_roots(roots: Roots, expected: &[f64]) {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-4;
+        for &root in roots.as_slice() {
+            assert!(expected.iter().any(|&e| close(root, e)), "{:?}", roots);
+        }
+        for &e in expected {
+            assert!(
+                roots.as_slice().iter().any(|&root| close(root, e)),
+                "{:?}",
+                roots
+            );
+        }
+    }
+
+    #[test]
+    fn quartic_with_four_distinct_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        let roots = solve_quartic([1.0, -10.0, 35.0, -50.0, 24.0]);
+        assert_eq!(roots.as_slice().len(), 4);
+        assert_roots(roots, &[1.0, 2.0, 3.0, 4.0]);
+
+        // The same scaled, with roots around zero
+        assert_roots(
+            solve_quartic([2.0, 0.0, -10.0, 0.0, 8.0]),
+            &[-2.0, -1.0, 1.0, 2.0],
+        );
+    }
+
+    #[test]
+    fn quartic_with_double_root() {
+        // (x - 1)^2 (x - 3)(x + 2)
+        assert_roots(
+            solve_quartic([1.0, -3.0, -3.0, 11.0, -6.0]),
+            &[1.0, 3.0, -2.0],
+        );
+        // (x - 1)^2 (x + 1)^2, touching the axis twice
+        assert_roots(solve_quartic([1.0, 0.0, -2.0, 0.0, 1.0]), &[-1.0, 1.0]);
+    }
+
+    #[test]
+    fn quartic_without_real_roots() {
+        // (x^2 + 1)(x^2 + 4)
+        assert!(solve_quartic([1.0, 0.0, 5.0, 0.0, 4.0])
+            .as_slice()
+            .is_empty());
+        // (x^2 - 2x + 2)(x^2 + 2x + 5)
+        assert!(solve_quartic([1.0, 0.0, 5.0, 6.0, 10.0])
+            .as_slice()
+            .is_empty());
+    }
+
+    #[test]
+    fn quartic_of_lower_degree() {
+        // (x - 1)(x - 2)(x + 3)
+        assert_roots(solve_quartic([0.0, 1.0, 0.0, -7.0, 6.0]), &[1.0, 2.0, -3.0]);
+        assert_roots(solve_quartic([0.0, 0.0, 1.0, -3.0, 2.0]), &[1.0, 2.0]);
+    }
+}
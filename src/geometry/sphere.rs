@@ -1,7 +1,12 @@
+use std::f32::consts::PI;
+
+use crate::geometry::aabb::Aabb;
 use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::plane::tangent_basis;
 use crate::geometry::ray::Ray;
 use crate::geometry::{Point3, Vec3};
 use crate::material::Material;
+use crate::sampler::{unit_sphere, Sampler};
 
 pub struct Sphere {
     pub center: Point3,
@@ -19,37 +24,106 @@ impl Sphere {
     }
 }
 
+// u follows the longitude from -x, v the latitude from -y
+fn uv(outward_normal: &Vec3) -> (f32, f32) {
+    let theta = (-outward_normal.y).clamp(-1.0, 1.0).acos();
+    let phi = (-outward_normal.z).atan2(outward_normal.x) + PI;
+
+    (phi / (2.0 * PI), theta / PI)
+}
+
+// Shared with the moving sphere, which only knows its center at the ray time
+pub(crate) fn hit_sphere<'a>(
+    center: &Point3,
+    radius: f32,
+    material: &'a (dyn Material + Send + Sync),
+    r: &Ray,
+    range: HitRange,
+) -> Option<HitRecord<'a>> {
+    let oc = r.orig - center;
+    let a = r.dir.length_squared();
+    let half_b = Vec3::dot(&oc, &r.dir);
+    let c = oc.length_squared() - radius * radius;
+
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < f32::EPSILON {
+        return Option::None;
+    }
+
+    let sqrtd = discriminant.sqrt();
+
+    // Find the neared root that lies in the acceptable range
+    let roots = [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a];
+    if let Some(valid_root) = roots
+        .iter()
+        .find(|root| range.start() <= *root && *root <= range.end())
+    {
+        let valid_hitpoint = r.at(*valid_root);
+        let outward_normal = (valid_hitpoint - center) / radius;
+        Option::from(HitRecord::new(
+            valid_hitpoint,
+            material,
+            &outward_normal,
+            *valid_root,
+            uv(&outward_normal),
+            r,
+        ))
+    } else {
+        Option::None
+    }
+}
+
+pub(crate) fn sphere_box(center: &Point3, radius: f32) -> Aabb {
+    let r = radius.abs();
+    let radius = Vec3::new(r, r, r);
+    Aabb::new(center - radius, center + radius)
+}
+
 impl Hittable for Sphere {
-    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord> {
-        let oc = r.orig - self.center;
-        let a = r.dir.length_squared();
-        let half_b = Vec3::dot(&oc, &r.dir);
-        let c = oc.length_squared() - self.radius * self.radius;
-
-        let discriminant = half_b * half_b - a * c;
-        if discriminant < f32::EPSILON {
-            return Option::None;
-        }
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        hit_sphere(&self.center, self.radius, self.material.as_ref(), r, range)
+    }
 
-        let sqrtd = discriminant.sqrt();
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::Some(sphere_box(&self.center, self.radius))
+    }
 
-        // Find the neared root that lies in the acceptable range
-        let roots = vec![(-half_b - sqrtd) / a, (-half_b + sqrtd) / a];
-        if let Some(valid_root) = roots
-            .iter()
-            .find(|root| range.start() <= *root && *root <= range.end())
+    // Uniform over the cone of directions subtended by the sphere, or over
+    // all directions from inside of it
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        if self
+            .hit(&ray, HitRange::new(0.001, f32::INFINITY))
+            .is_none()
         {
-            let valid_hitpoint = r.at(*valid_root);
-            let outward_normal = (valid_hitpoint - self.center) / self.radius;
-            Option::from(HitRecord::new(
-                valid_hitpoint,
-                self.material.as_ref(),
-                &outward_normal,
-                *valid_root,
-                r,
-            ))
-        } else {
-            Option::None
+            return 0.0;
+        }
+
+        let distance_squared = (self.center - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return 1.0 / (4.0 * PI);
         }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn random_direction(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = self.center - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return unit_sphere(sampler.get_2d());
+        }
+
+        let (r1, r2) = sampler.get_2d();
+        let z = 1.0 + r2 * ((1.0 - radius_squared / distance_squared).sqrt() - 1.0);
+        let (sin_phi, cos_phi) = (2.0 * PI * r1).sin_cos();
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+
+        let w = Vec3::unit_vector(&direction);
+        let (u, v) = tangent_basis(&w);
+        sin_theta * (cos_phi * u + sin_phi * v) + z * w
     }
 }
@@ -0,0 +1,180 @@
+use std::f32::consts::PI;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::cylinder::azimuth;
+use crate::geometry::disk::disk_box;
+use crate::geometry::frame::Frame;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::solver::solve_quartic;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Ring swept by a circle of the minor radius around the axis, at the major
+// radius from the center
+pub struct Torus {
+    frame: Frame,
+    pub major_radius: f32,
+    pub minor_radius: f32,
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: f32,
+        minor_radius: f32,
+        m: Box<dyn Material + Send + Sync>,
+    ) -> Self {
+        Torus {
+            frame: Frame::new(center, &axis),
+            major_radius: major_radius.abs(),
+            minor_radius: minor_radius.abs(),
+            material: m,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local_ray(r);
+
+        // Solve (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2) in double precision
+        // along the normalized direction
+        let length = local.dir.length() as f64;
+        let (ox, oy, oz) = (
+            local.orig.x as f64,
+            local.orig.y as f64,
+            local.orig.z as f64,
+        );
+        let (dx, dy, dz) = (
+            local.dir.x as f64 / length,
+            local.dir.y as f64 / length,
+            local.dir.z as f64 / length,
+        );
+        let sq_major = (self.major_radius as f64).powi(2);
+        let sq_minor = (self.minor_radius as f64).powi(2);
+
+        let f = ox * dx + oy * dy + oz * dz;
+        let g = ox * ox + oy * oy + oz * oz + sq_major - sq_minor;
+        let coefficients = [
+            1.0,
+            4.0 * f,
+            4.0 * f * f + 2.0 * g - 4.0 * sq_major * (dx * dx + dz * dz),
+            4.0 * f * g - 8.0 * sq_major * (ox * dx + oz * dz),
+            g * g - 4.0 * sq_major * (ox * ox + oz * oz),
+        ];
+        let evaluate = |s: f64| {
+            let value = coefficients.iter().fold(0.0, |acc, c| acc * s + c);
+            let derivative = coefficients[..4]
+                .iter()
+                .enumerate()
+                .fold(0.0, |acc, (i, c)| acc * s + (4 - i) as f64 * c);
+            (value, derivative)
+        };
+
+        let t = solve_quartic(coefficients)
+            .as_slice()
+            .iter()
+            .map(|&s| {
+                // A few Newton steps recover the precision lost by the solver
+                let mut s = s;
+                for _ in 0..2 {
+                    let (value, derivative) = evaluate(s);
+                    if derivative != 0.0 {
+                        s -= value / derivative;
+                    }
+                }
+                (s / length) as f32
+            })
+            .filter(|t| range.contains(t))
+            .min_by(|a, b| a.total_cmp(b))?;
+
+        let p = local.at(t);
+        let ring = Vec3::new(p.x, 0.0, p.z);
+        let ring_distance = ring.length();
+        let tube_center = if ring_distance > 0.0 {
+            self.major_radius / ring_distance * ring
+        } else {
+            ring
+        };
+        let normal = Vec3::unit_vector(&(p - tube_center));
+
+        // u goes around the axis, v around the tube
+        let phi = p.y.atan2(ring_distance - self.major_radius);
+        Option::Some(HitRecord::new(
+            r.at(t),
+            self.material.as_ref(),
+            &self.frame.to_world_vector(&normal),
+            t,
+            (azimuth(&p), (phi + PI) / (2.0 * PI)),
+            r,
+        ))
+    }
+
+    // The box of the central circle grown by the tube
+    fn bounding_box(&self) -> Option<Aabb> {
+        let circle = disk_box(&self.frame.origin, &self.frame.axis, self.major_radius);
+        let r = Vec3::new(self.minor_radius, self.minor_radius, self.minor_radius);
+        Option::Some(Aabb::new(circle.min - r, circle.max + r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::material::lambertian::Lambertian;
+
+    // Ring of radius 2 around the y axis, with a tube of radius 0.5
+    fn torus() -> Torus {
+        Torus::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            2.0,
+            0.5,
+            Box::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn hits_near_side_of_tube() {
+        let torus = torus();
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -2.0), 0.0);
+        let rec = torus.hit(&r, HitRange::new(0.001, f32::INFINITY)).unwrap();
+        assert!((rec.t() - 1.25).abs() < 1e-5);
+        assert!((*rec.p() - Point3::new(0.0, 0.0, 2.5)).length() < 1e-5);
+        assert!((*rec.normal() - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-5);
+        assert!(rec.front_face());
+    }
+
+    #[test]
+    fn hits_far_side_through_hole() {
+        // Past the near tube the ray crosses the hole and enters the far one
+        // from the inside of the ring
+        let torus = torus();
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let rec = torus.hit(&r, HitRange::new(4.0, f32::INFINITY)).unwrap();
+        assert!((rec.t() - 6.5).abs() < 1e-4);
+        assert!((*rec.normal() - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-4);
+        assert!(rec.front_face());
+
+        let rec = torus.hit(&r, HitRange::new(3.0, 4.0)).unwrap();
+        assert!((rec.t() - 3.5).abs() < 1e-4);
+        assert!(!rec.front_face());
+    }
+
+    #[test]
+    fn misses_along_axis_and_above_tube() {
+        let along_axis = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        assert!(torus()
+            .hit(&along_axis, HitRange::new(0.001, f32::INFINITY))
+            .is_none());
+
+        let above = Ray::new(Point3::new(0.0, 0.6, 5.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        assert!(torus()
+            .hit(&above, HitRange::new(0.001, f32::INFINITY))
+            .is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::geometry::{Point3, Vec3};
+use crate::material::Material;
+
+// Watertight ray/triangle intersection (Woop, Benthin and Wald, JCGT 2013).
+// Returns the distance along the ray and the barycentric weights of each vertex.
+pub(crate) fn intersect(
+    r: &Ray,
+    vertices: [&Point3; 3],
+    range: &HitRange,
+) -> Option<(f32, [f32; 3])> {
+    // Permute axes so that the ray direction is mostly along z
+    let abs_dir = Vec3::new(r.dir.x.abs(), r.dir.y.abs(), r.dir.z.abs());
+    let kz = if abs_dir.x > abs_dir.y {
+        if abs_dir.x > abs_dir.z {
+            0
+        } else {
+            2
+        }
+    } else if abs_dir.y > abs_dir.z {
+        1
+    } else {
+        2
+    };
+    let (kx, ky) = if r.dir[kz] < 0.0 {
+        ((kz + 2) % 3, (kz + 1) % 3)
+    } else {
+        ((kz + 1) % 3, (kz + 2) % 3)
+    };
+
+    // Shear constants
+    let sz = 1.0 / r.dir[kz];
+    let sx = r.dir[kx] * sz;
+    let sy = r.dir[ky] * sz;
+
+    let [a, b, c] = [
+        vertices[0] - r.orig,
+        vertices[1] - r.orig,
+        vertices[2] - r.orig,
+    ];
+    let (ax, ay) = (a[kx] - sx * a[kz], a[ky] - sy * a[kz]);
+    let (bx, by) = (b[kx] - sx * b[kz], b[ky] - sy * b[kz]);
+    let (cx, cy) = (c[kx] - sx * c[kz], c[ky] - sy * c[kz]);
+
+    let mut u = cx * by - cy * bx;
+    let mut v = ax * cy - ay * cx;
+    let mut w = bx * ay - by * ax;
+
+    // Fall back to double precision on edges
+    if u == 0.0 || v == 0.0 || w == 0.0 {
+        u = (cx as f64 * by as f64 - cy as f64 * bx as f64) as f32;
+        v = (ax as f64 * cy as f64 - ay as f64 * cx as f64) as f32;
+        w = (bx as f64 * ay as f64 - by as f64 * ax as f64) as f32;
+    }
+
+    if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+        return Option::None;
+    }
+
+    let det = u + v + w;
+    if det == 0.0 {
+        return Option::None;
+    }
+
+    // Hits on an edge belong to one of the triangles sharing it: the edge
+    // functions of both are exactly zero, and only the triangle the edge
+    // runs upwards in, once both face the same way, keeps the hit
+    // (top-left rule)
+    let owns = |function: f32, (ex, ey): (f32, f32)| {
+        let (ex, ey) = if det < 0.0 { (-ex, -ey) } else { (ex, ey) };
+        function != 0.0 || ey > 0.0 || (ey == 0.0 && ex > 0.0)
+    };
+    if !owns(u, (cx - bx, cy - by)) || !owns(v, (ax - cx, ay - cy)) || !owns(w, (bx - ax, by - ay))
+    {
+        return Option::None;
+    }
+
+    let t_scaled = u * sz * a[kz] + v * sz * b[kz] + w * sz * c[kz];
+    let t = t_scaled / det;
+    if t < *range.start() || t > *range.end() {
+        return Option::None;
+    }
+
+    Option::Some((t, [u / det, v / det, w / det]))
+}
+
+pub(crate) fn bounding_box(vertices: [&Point3; 3]) -> Aabb {
+    Aabb::new(
+        Vec3::min(&Vec3::min(vertices[0], vertices[1]), vertices[2]),
+        Vec3::max(&Vec3::max(vertices[0], vertices[1]), vertices[2]),
+    )
+}
+
+// Counter-clockwise vertices face the outside
+pub(crate) fn geometric_normal(vertices: [&Point3; 3]) -> Vec3 {
+    let n = Vec3::cross(&(vertices[1] - vertices[0]), &(vertices[2] - vertices[0]));
+    (1.0 / n.length()) * n
+}
+
+pub struct Triangle {
+    pub vertices: [Point3; 3],
+    pub material: Box<dyn Material + Send + Sync>,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, m: Box<dyn Material + Send + Sync>) -> Self {
+        Triangle {
+            vertices: [v0, v1, v2],
+            material: m,
+        }
+    }
+
+    fn vertex_refs(&self) -> [&Point3; 3] {
+        [&self.vertices[0], &self.vertices[1], &self.vertices[2]]
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, range: HitRange) -> Option<HitRecord<'_>> {
+        let (t, barycentrics) = intersect(r, self.vertex_refs(), &range)?;
+
+        Option::Some(HitRecord::new(
+            r.at(t),
+            self.material.as_ref(),
+            &geometric_normal(self.vertex_refs()),
+            t,
+            (barycentrics[1], barycentrics[2]),
+            r,
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Option::Some(bounding_box(self.vertex_refs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::material::lambertian::Lambertian;
+
+    // Counter-clockwise triangles of a 4 x 4 grid of unit squares in the
+    // z = 0 plane, split along their diagonals
+    fn grid() -> Vec<[Point3; 3]> {
+        let mut triangles = vec![];
+        for y in 0..4 {
+            for x in 0..4 {
+                let (x, y) = (x as f32, y as f32);
+                let corner = |dx, dy| Point3::new(x + dx, y + dy, 0.0);
+                triangles.push([corner(0.0, 0.0), corner(1.0, 0.0), corner(1.0, 1.0)]);
+                triangles.push([corner(0.0, 0.0), corner(1.0, 1.0), corner(0.0, 1.0)]);
+            }
+        }
+        triangles
+    }
+
+    // Number of triangles of the grid the ray through p hits
+    fn hit_count(triangles: &[[Point3; 3]], p: Point3, dir: Vec3) -> usize {
+        let r = Ray::new(p - dir, dir, 0.0);
+        let range = HitRange::new(0.0, f32::INFINITY);
+        triangles
+            .iter()
+            .filter(|[a, b, c]| intersect(&r, [a, b, c], &range).is_some())
+            .count()
+    }
+
+    #[test]
+    fn shared_edges_and_vertices_are_hit_once() {
+        let triangles = grid();
+        for dir in [
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.25, 0.5, -1.0),
+            Vec3::new(-0.75, 0.125, 1.0),
+        ] {
+            // Inner vertices, and points along the edges and diagonals
+            for i in 1..32 {
+                for j in 1..32 {
+                    let p = Point3::new(i as f32 / 8.0, j as f32 / 8.0, 0.0);
+                    assert_eq!(hit_count(&triangles, p, dir), 1, "{:?} {:?}", p, dir);
+                }
+            }
+            // Points along the diagonals, not all of them exactly on one
+            for i in 1..1000 {
+                let s = i as f32 / 1000.0;
+                let p = Point3::new(0.5 + 3.0 * s, 0.5 + 3.0 * s * 1.000_001, 0.0);
+                assert_eq!(hit_count(&triangles, p, dir), 1, "{:?} {:?}", p, dir);
+            }
+        }
+    }
+
+    #[test]
+    fn uv_are_barycentric_coordinates() {
+        let triangle = Triangle::new(
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(3.0, 0.0, 0.0),
+            Point3::new(1.0, 4.0, 0.0),
+            Box::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))),
+        );
+        let r = Ray::new(Point3::new(1.5, 2.0, 1.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let rec = triangle
+            .hit(&r, HitRange::new(0.001, f32::INFINITY))
+            .unwrap();
+
+        let (u, v) = rec.uv();
+        assert!((u - 0.25).abs() < 1e-6 && (v - 0.5).abs() < 1e-6);
+        assert!((*rec.normal() - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-6);
+        assert!(rec.front_face());
+    }
+}
@@ -0,0 +1,88 @@
+//! Wavefront OBJ meshes and their MTL materials.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub mod mtl;
+pub mod obj;
+
+/// Why a mesh or its materials could not be loaded.
+#[derive(Debug)]
+pub enum ImportError {
+    /// A file could not be read.
+    Io(PathBuf, io::Error),
+    /// An image could not be decoded.
+    Decode(PathBuf, String),
+    /// A file is malformed.
+    Parse {
+        /// The file at fault.
+        path: PathBuf,
+        /// Line of the file at fault.
+        line: usize,
+        /// What is wrong with it.
+        message: String,
+    },
+}
+
+impl ImportError {
+    fn parse(path: &Path, line: usize, message: String) -> Self {
+        ImportError::Parse {
+            path: path.to_path_buf(),
+            line,
+            message,
+        }
+    }
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            ImportError::Decode(path, message) => write!(f, "{}: {}", path.display(), message),
+            ImportError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ImportError::Io(_, err) => Some(err),
+            ImportError::Decode(..) | ImportError::Parse { .. } => Option::None,
+        }
+    }
+}
+
+// Splits a file into (line number, keyword, arguments), skipping comments and
+// blank lines
+fn statements(content: &str) -> impl Iterator<Item = (usize, &str, Vec<&str>)> {
+    content.lines().enumerate().filter_map(|(i, line)| {
+        let line = line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next()?;
+        Some((i + 1, keyword, tokens.collect()))
+    })
+}
+
+fn parse_floats(args: &[&str], min: usize, max: usize) -> Result<Vec<f32>, String> {
+    if args.len() < min || args.len() > max {
+        return Err(if min == max {
+            format!("expected {} numbers, found {}", min, args.len())
+        } else {
+            format!("expected {} to {} numbers, found {}", min, max, args.len())
+        });
+    }
+
+    args.iter()
+        .map(|arg| {
+            arg.parse::<f32>()
+                .map_err(|_| format!("invalid number '{}'", arg))
+        })
+        .collect()
+}
@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::Path;
+
+use crate::color::Color;
+use crate::import::{parse_floats, statements, ImportError};
+use crate::material::dielectric::Dielectric;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::material::Material;
+
+#[derive(Debug, Clone)]
+pub struct MtlMaterial {
+    pub name: String,
+    pub diffuse: Color,
+    pub specular: Color,
+    pub shininess: f32,
+    pub ior: f32,
+    pub dissolve: f32,
+}
+
+impl MtlMaterial {
+    pub fn new(name: &str) -> Self {
+        MtlMaterial {
+            name: name.to_string(),
+            diffuse: Color::new(0.8, 0.8, 0.8),
+            specular: Color::new(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            ior: 1.5,
+            dissolve: 1.0,
+        }
+    }
+
+    // Transparent materials become glass, otherwise the dominant of the
+    // diffuse and specular colors selects between a matte and a metal surface
+    pub fn to_material(&self) -> Box<dyn Material + Send + Sync> {
+        let max_component = |c: &Color| c.x.max(c.y).max(c.z);
+
+        if self.dissolve < 1.0 {
+            Box::new(Dielectric::new(self.ior))
+        } else if max_component(&self.specular) > max_component(&self.diffuse) {
+            // Map the Phong exponent onto a roughness
+            let fuzz = (2.0 / (self.shininess.max(0.0) + 2.0)).sqrt();
+            Box::new(Metal::new(&self.specular, fuzz))
+        } else {
+            Box::new(Lambertian::new(&self.diffuse))
+        }
+    }
+}
+
+pub fn load_mtl(path: &Path) -> Result<Vec<MtlMaterial>, ImportError> {
+    let content =
+        fs::read_to_string(path).map_err(|err| ImportError::Io(path.to_path_buf(), err))?;
+    parse_mtl(path, &content)
+}
+
+pub fn parse_mtl(path: &Path, content: &str) -> Result<Vec<MtlMaterial>, ImportError> {
+    let mut materials: Vec<MtlMaterial> = vec![];
+
+    for (line, keyword, args) in statements(content) {
+        let error = |message: String| ImportError::parse(path, line, message);
+
+        if keyword == "newmtl" {
+            if args.len() != 1 {
+                return Err(error("newmtl expects a single material name".to_string()));
+            }
+            materials.push(MtlMaterial::new(args[0]));
+            continue;
+        }
+
+        let current = materials.last_mut();
+        let parse_color = || {
+            let values = parse_floats(&args, 1, 3).map_err(error)?;
+            // A single value is a grey level
+            Ok(match values[..] {
+                [grey] => Color::new(grey, grey, grey),
+                [r, g, b] => Color::new(r, g, b),
+                _ => return Err(error("expected 1 or 3 color components".to_string())),
+            })
+        };
+        let parse_scalar = || parse_floats(&args, 1, 1).map(|v| v[0]).map_err(error);
+
+        match (keyword, current) {
+            ("Kd", Some(material)) => material.diffuse = parse_color()?,
+            ("Ks", Some(material)) => material.specular = parse_color()?,
+            ("Ns", Some(material)) => material.shininess = parse_scalar()?,
+            ("Ni", Some(material)) => material.ior = parse_scalar()?,
+            ("d", Some(material)) => material.dissolve = parse_scalar()?,
+            ("Tr", Some(material)) => material.dissolve = 1.0 - parse_scalar()?,
+            ("Kd" | "Ks" | "Ns" | "Ni" | "d" | "Tr", None) => {
+                return Err(error(format!(
+                    "'{}' appears before any newmtl statement",
+                    keyword
+                )))
+            }
+            // Ambient, emissive, illumination models and texture maps are not supported
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_material_values() {
+        let materials = parse_mtl(
+            Path::new("test.mtl"),
+            "newmtl matte\nKd 0.5\n\nnewmtl shiny\nKs 0.9 0.8 0.7\nNs 98\nTr 0.25\nNi 1.33\n",
+        )
+        .unwrap();
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].name, "matte");
+        assert_eq!(materials[0].diffuse, Color::new(0.5, 0.5, 0.5));
+        assert_eq!(materials[1].specular, Color::new(0.9, 0.8, 0.7));
+        assert_eq!(materials[1].shininess, 98.0);
+        assert_eq!(materials[1].dissolve, 0.75);
+        assert_eq!(materials[1].ior, 1.33);
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_statements() {
+        for (content, line, message) in [
+            (
+                "# header\nKd 1 1 1\n",
+                2,
+                "'Kd' appears before any newmtl statement",
+            ),
+            ("newmtl a b\n", 1, "newmtl expects a single material name"),
+            ("newmtl a\nKd 1 1\n", 2, "expected 1 or 3 color components"),
+            ("newmtl a\nNs\n", 2, "expected 1 numbers, found 0"),
+        ] {
+            match parse_mtl(Path::new("test.mtl"), content) {
+                Err(ImportError::Parse {
+                    line: error_line,
+                    message: error_message,
+                    ..
+                }) => assert_eq!((error_line, error_message.as_str()), (line, message)),
+                _ => panic!("'{}' was not reported", content),
+            }
+        }
+    }
+}
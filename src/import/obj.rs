@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::geometry::mesh::TriangleMesh;
+use crate::geometry::{Point3, Vec3};
+use crate::import::mtl::{load_mtl, MtlMaterial};
+use crate::import::{parse_floats, statements, ImportError};
+
+// (position, uv, normal) indices of a face corner
+type Corner = (usize, Option<usize>, Option<usize>);
+
+// Faces sharing a group and a material end up in the same mesh
+struct MeshBuilder {
+    material: Option<String>,
+    corners: HashMap<Corner, usize>,
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f32, f32)>,
+    faces: Vec<[usize; 3]>,
+    missing_normals: bool,
+    missing_uvs: bool,
+}
+
+impl MeshBuilder {
+    fn new(material: Option<&str>) -> Self {
+        MeshBuilder {
+            material: material.map(str::to_string),
+            corners: HashMap::new(),
+            positions: vec![],
+            normals: vec![],
+            uvs: vec![],
+            faces: vec![],
+            missing_normals: false,
+            missing_uvs: false,
+        }
+    }
+
+    fn vertex(&mut self, corner: Corner, obj: &ObjData) -> usize {
+        if let Some(index) = self.corners.get(&corner) {
+            return *index;
+        }
+        let next_index = self.positions.len();
+
+        let (position, uv, normal) = corner;
+        self.positions.push(obj.positions[position]);
+        self.uvs.push(uv.map_or((0.0, 0.0), |i| obj.uvs[i]));
+        self.normals
+            .push(normal.map_or(Vec3::default(), |i| obj.normals[i]));
+        self.missing_uvs |= uv.is_none();
+        self.missing_normals |= normal.is_none();
+
+        self.corners.insert(corner, next_index);
+        next_index
+    }
+
+    fn build(mut self, materials: &HashMap<String, MtlMaterial>) -> TriangleMesh {
+        // Attributes are all or nothing for a mesh
+        if self.missing_normals {
+            self.normals.clear();
+        }
+        if self.missing_uvs {
+            self.uvs.clear();
+        }
+
+        let material = self
+            .material
+            .as_ref()
+            .and_then(|name| materials.get(name))
+            .cloned()
+            .unwrap_or_else(|| MtlMaterial::new("default"));
+
+        TriangleMesh::new(
+            self.positions,
+            self.normals,
+            self.uvs,
+            self.faces,
+            material.to_material(),
+        )
+    }
+}
+
+#[derive(Default)]
+struct ObjData {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f32, f32)>,
+}
+
+// OBJ indices start at 1, negative ones are relative to the end of the list
+fn resolve_index(token: &str, count: usize, kind: &str) -> Result<usize, String> {
+    let index = token
+        .parse::<i64>()
+        .map_err(|_| format!("invalid {} index '{}'", kind, token))?;
+
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        Err(format!(
+            "{} index {} out of range, {} defined so far",
+            kind, index, count
+        ))
+    } else {
+        Ok(resolved as usize)
+    }
+}
+
+fn parse_corner(token: &str, obj: &ObjData) -> Result<Corner, String> {
+    let parts: Vec<&str> = token.split('/').collect();
+    if parts.len() > 3 || parts[0].is_empty() {
+        return Err(format!("invalid face vertex '{}'", token));
+    }
+
+    let position = resolve_index(parts[0], obj.positions.len(), "vertex")?;
+    let uv = match parts.get(1) {
+        Some(part) if !part.is_empty() => Some(resolve_index(part, obj.uvs.len(), "texture")?),
+        _ => Option::None,
+    };
+    let normal = match parts.get(2) {
+        Some(part) if !part.is_empty() => Some(resolve_index(part, obj.normals.len(), "normal")?),
+        Some(_) => return Err(format!("invalid face vertex '{}'", token)),
+        None => Option::None,
+    };
+
+    Ok((position, uv, normal))
+}
+
+// One mesh per group and material
+pub fn load_obj(path: &Path) -> Result<Vec<TriangleMesh>, ImportError> {
+    let content =
+        fs::read_to_string(path).map_err(|err| ImportError::Io(path.to_path_buf(), err))?;
+    parse_obj(path, &content)
+}
+
+// Material libraries are looked up relatively to the OBJ file
+pub fn parse_obj(path: &Path, content: &str) -> Result<Vec<TriangleMesh>, ImportError> {
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut obj = ObjData::default();
+    let mut materials: HashMap<String, MtlMaterial> = HashMap::new();
+    let mut builders: Vec<MeshBuilder> = vec![];
+    let mut builder_indices: HashMap<(String, Option<String>), usize> = HashMap::new();
+
+    let mut group = "default".to_string();
+    let mut material: Option<String> = Option::None;
+
+    for (line, keyword, args) in statements(content) {
+        let error = |message: String| ImportError::parse(path, line, message);
+
+        match keyword {
+            "v" => {
+                // Optional w and vertex colors are ignored
+                let values = parse_floats(&args, 3, 7).map_err(error)?;
+                obj.positions
+                    .push(Point3::new(values[0], values[1], values[2]));
+            }
+            "vt" => {
+                let values = parse_floats(&args, 1, 3).map_err(error)?;
+                obj.uvs.push((values[0], *values.get(1).unwrap_or(&0.0)));
+            }
+            "vn" => {
+                let values = parse_floats(&args, 3, 3).map_err(error)?;
+                obj.normals.push(Vec3::new(values[0], values[1], values[2]));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error(format!(
+                        "a face needs at least 3 vertices, found {}",
+                        args.len()
+                    )));
+                }
+                let corners = args
+                    .iter()
+                    .map(|token| parse_corner(token, &obj))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+
+                let key = (group.clone(), material.clone());
+                let builder_index = *builder_indices.entry(key).or_insert_with(|| {
+                    builders.push(MeshBuilder::new(material.as_deref()));
+                    builders.len() - 1
+                });
+                let builder = &mut builders[builder_index];
+
+                // Fan triangulation, polygons are assumed to be convex
+                let indices: Vec<usize> = corners
+                    .into_iter()
+                    .map(|corner| builder.vertex(corner, &obj))
+                    .collect();
+                for i in 1..indices.len() - 1 {
+                    builder.faces.push([indices[0], indices[i], indices[i + 1]]);
+                }
+            }
+            "g" | "o" => {
+                group = if args.is_empty() {
+                    "default".to_string()
+                } else {
+                    args.join(" ")
+                };
+            }
+            "usemtl" => {
+                if args.len() != 1 {
+                    return Err(error("usemtl expects a single material name".to_string()));
+                }
+                if !materials.contains_key(args[0]) {
+                    return Err(error(format!("unknown material '{}'", args[0])));
+                }
+                material = Some(args[0].to_string());
+            }
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(error("mtllib expects at least one file name".to_string()));
+                }
+                for file in args {
+                    load_mtl(&directory.join(file))?
+                        .into_iter()
+                        .for_each(|mtl| {
+                            materials.insert(mtl.name.clone(), mtl);
+                        });
+                }
+            }
+            // Smoothing groups, lines, points and free-form geometry are not supported
+            _ => {}
+        }
+    }
+
+    Ok(builders
+        .into_iter()
+        .filter(|builder| !builder.faces.is_empty())
+        .map(|builder| builder.build(&materials))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+    use crate::geometry::ray::Ray;
+
+    fn parse(content: &str) -> Vec<TriangleMesh> {
+        match parse_obj(Path::new("test.obj"), content) {
+            Ok(meshes) => meshes,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn error_line(content: &str) -> (usize, String) {
+        match parse_obj(Path::new("test.obj"), content) {
+            Err(ImportError::Parse { line, message, .. }) => (line, message),
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("parsed '{}'", content),
+        }
+    }
+
+    // Nearest hit of a ray looking down the z axis at (x, y)
+    fn hit(meshes: &[TriangleMesh], x: f32, y: f32) -> Option<HitRecord<'_>> {
+        let r = Ray::new(Point3::new(x, y, 1.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        meshes
+            .iter()
+            .filter_map(|mesh| mesh.hit(&r, HitRange::new(0.001, f32::INFINITY)))
+            .min_by(|a, b| a.t().total_cmp(&b.t()))
+    }
+
+    #[test]
+    fn fan_triangulates_polygons() {
+        let meshes = parse(
+            "v 0 0 0\nv 2 0 0\nv 3 1 0\nv 1 2 0\nv -1 1 0\n\
+             f 1 2 3 4 5\n",
+        );
+        assert_eq!(meshes.len(), 1);
+
+        // Inside each of the three triangles of the fan
+        for (x, y) in [(1.6, 0.3), (1.3, 1.0), (0.0, 1.0)] {
+            assert!(hit(&meshes, x, y).is_some(), "({}, {})", x, y);
+        }
+        assert!(hit(&meshes, 2.9, 0.1).is_none());
+        assert!(hit(&meshes, -0.9, 0.1).is_none());
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_last_vertex() {
+        let meshes = parse(
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             v 10 0 0\nv 11 0 0\nv 10 1 0\n\
+             f -3 -2 -1\n",
+        );
+        assert!(hit(&meshes, 10.2, 0.2).is_some());
+        assert!(hit(&meshes, 0.2, 0.2).is_none());
+    }
+
+    #[test]
+    fn corners_take_uvs_and_normals() {
+        let meshes = parse(
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             vt 0 0\nvt 1 0\nvt 0 1\n\
+             vn 0.6 0 0.8\n\
+             f 1/1/1 2/2/1 3/3/1\n",
+        );
+        let rec = hit(&meshes, 0.25, 0.5).unwrap();
+        let (u, v) = rec.uv();
+        assert!((u - 0.25).abs() < 1e-5 && (v - 0.5).abs() < 1e-5);
+        assert!((*rec.normal() - Vec3::new(0.6, 0.0, 0.8)).length() < 1e-5);
+
+        // Normals without uvs, and positions alone
+        let meshes = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//1\n");
+        assert!(hit(&meshes, 0.25, 0.25).is_some());
+        let meshes = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1/1 2/1 3/1\n");
+        assert!(hit(&meshes, 0.25, 0.25).is_some());
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_statements() {
+        let triangle = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+        let cases = [
+            ("f 1 2 4\n", "vertex index 4 out of range, 3 defined so far"),
+            ("f 0 1 2\n", "vertex index 0 out of range, 3 defined so far"),
+            (
+                "f -4 1 2\n",
+                "vertex index -4 out of range, 3 defined so far",
+            ),
+            (
+                "f 1/1 2 3\n",
+                "texture index 1 out of range, 0 defined so far",
+            ),
+            (
+                "f 1//2 2 3\n",
+                "normal index 2 out of range, 0 defined so far",
+            ),
+            ("f 1/ 2 3/x 4\n", "invalid texture index 'x'"),
+            ("f 1// 2 3\n", "invalid face vertex '1//'"),
+            ("f 1 2\n", "a face needs at least 3 vertices, found 2"),
+            ("v 1 2\n", "expected 3 to 7 numbers, found 2"),
+            ("usemtl red\n", "unknown material 'red'"),
+        ];
+        for (statement, message) in cases {
+            let content = format!("{}\n# comment\n{}", triangle, statement);
+            assert_eq!(error_line(&content), (6, message.to_string()));
+        }
+    }
+
+    #[test]
+    fn material_libraries_are_relative_to_the_obj_file() {
+        let directory = std::env::temp_dir().join(format!("raytracing-obj-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("materials.mtl"),
+            "newmtl red\nKd 1 0 0\nnewmtl glass\nd 0.5\n",
+        )
+        .unwrap();
+
+        // Faces are split by material
+        let content = "mtllib materials.mtl\n\
+                       v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\n\
+                       usemtl red\nf 1 2 3\nusemtl glass\nf 2 4 3\n";
+        let meshes = parse_obj(&directory.join("mesh.obj"), content);
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(meshes.map(|meshes| meshes.len()).ok(), Some(2));
+
+        match parse_obj(&directory.join("mesh.obj"), content) {
+            Err(ImportError::Io(path, _)) => assert_eq!(path, directory.join("materials.mtl")),
+            _ => panic!("missing material library was not reported"),
+        }
+    }
+}
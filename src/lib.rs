@@ -0,0 +1,40 @@
+//! A path tracer grown out of "Ray Tracing in One Weekend".
+//!
+//! Scenes are either loaded from TOML files or one of the built-in scenes of
+//! [`Scene`], then rendered into a [`Framebuffer`] of linear radiance that
+//! [`output`] writes in several image formats.
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use std::path::Path;
+//!
+//! use raytracing::output::{write_image, OutputFormat};
+//! use raytracing::{render, RenderSettings, Scene};
+//!
+//! let scene = Scene::load(Path::new("scenes/csg.toml")).unwrap();
+//! let image = render(scene, &RenderSettings::default());
+//!
+//! let mut file = File::create("csg.png").unwrap();
+//! write_image(&mut file, &image, OutputFormat::Png).unwrap();
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod color;
+pub(crate) mod environment;
+pub mod film;
+pub(crate) mod geometry;
+pub(crate) mod import;
+pub(crate) mod material;
+pub mod output;
+pub mod render;
+pub mod sampler;
+pub mod scene;
+pub(crate) mod texture;
+
+pub use geometry::camera::Camera;
+pub use geometry::{Point3, Vec3};
+pub use import::ImportError;
+pub use output::framebuffer::Framebuffer;
+pub use render::{render, RenderSettings, Renderer};
+pub use scene::Scene;
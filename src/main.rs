@@ -1,97 +1,201 @@
-use std::convert::TryInto;
-use std::io::stdout;
-
-use indicatif::{ParallelProgressIterator, ProgressIterator};
-use rand::Rng;
-use rayon::prelude::*;
-
-use geometry::camera::Camera;
-use geometry::hittable::{HitRange, Hittable, HittableList};
-use geometry::ray::Ray;
-use geometry::{Point3, Vec3};
-
-use crate::color::{write_color, Color};
-
-mod color;
-mod geometry;
-mod material;
-
-fn ray_color(r: &Ray, world: &dyn Hittable, depth: &mut impl Iterator<Item = u8>) -> Color {
-    if depth.next().is_some() {
-        if let Some(rec) = world.hit(r, HitRange::new(0.001, f32::INFINITY)) {
-            if let Some(scatter_record) = rec.material().scatter(r, &rec) {
-                return scatter_record.attenuation * ray_color(&scatter_record.ray, world, depth);
-            } else {
-                return Color::new(0.0, 0.0, 0.0);
-            }
-        }
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, stdout, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use structopt::StructOpt;
+
+use raytracing::output::write_image;
+use raytracing::render::{Checkpoint, TimeLimit};
+use raytracing::{RenderSettings, Renderer, Scene};
+
+use cli::{BuiltinScene, Options};
+
+mod cli;
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
 
-        let unit_direction = Vec3::unit_vector(&r.dir);
-        let t = 0.5 * (unit_direction.y + 1.0);
-        (1.0 - t) * Color::new(1.0, 1.0, 1.0) + t * Color::new(0.5, 0.7, 1.0)
-    } else {
-        Color::new(0.0, 0.0, 0.0)
-    }
+// Written next to the checkpoint and renamed over it, so that an interrupted
+// write never loses the previous checkpoint
+fn write_checkpoint(path: &Path, checkpoint: &Checkpoint) -> io::Result<()> {
+    let mut temporary = OsString::from(path);
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+
+    checkpoint.write(&mut BufWriter::new(File::create(&temporary)?))?;
+    fs::rename(&temporary, path)
 }
 
 fn main() {
-    // Image
-    let aspect_ratio = 3.0f32 / 2.0f32;
-    let image_width = 1200i32;
-    let image_height = ((image_width as f32) / aspect_ratio) as i32;
-    let samples_per_pixel = 500i32;
-    let max_depth = 50u8;
-
-    // World
-    let world = HittableList::random_scene();
-
-    // Camera
-    let lookfrom = Point3::new(13.0, 2.0, 3.0);
-    let lookat = Point3::new(0.0, 0.0, 0.0);
-
-    let cam = Camera::new(
-        lookfrom,
-        lookat,
-        Vec3::new(0.0, 1.0, 0.0),
-        20.0,
-        aspect_ratio,
-        0.1,
-        10.0,
-    );
-
-    // Compute pixel lines in parallel
-    let mut lines: Vec<_> = (0..image_height as usize)
-        .into_par_iter()
-        .progress_count(image_height.try_into().unwrap())
-        .map(|j| {
-            let mut rng = rand::thread_rng();
-
-            let mut line: Vec<Color> = Vec::with_capacity(image_width as usize);
-            line.resize(image_width as usize, Color::new(0.0, 0.0, 0.0));
-
-            line.iter_mut().enumerate().for_each(|(i, pix)| {
-                for _ in 0..samples_per_pixel {
-                    let u = (i as f32 + rng.gen::<f32>()) / ((image_width - 1) as f32);
-                    let v = (j as f32 + rng.gen::<f32>()) / ((image_height - 1) as f32);
-
-                    let r = cam.get_ray(u, v);
-                    *pix = *pix + ray_color(&r, &world, &mut (0..max_depth));
-                }
-            });
-
-            (j, line)
+    let options = Options::from_args();
+
+    if let Some(threads) = options.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    }
+
+    let format = options
+        .output_format()
+        .unwrap_or_else(|err| exit_with_error(&err));
+    let heatmap_format = options
+        .heatmap_format()
+        .unwrap_or_else(|err| exit_with_error(&err));
+
+    let checkpoint = options.resume.as_ref().map(|path| {
+        File::open(path)
+            .and_then(|file| Checkpoint::read(&mut BufReader::new(file)))
+            .unwrap_or_else(|err| exit_with_error(&format!("{}: {}", path.display(), err)))
+    });
+
+    // Scene, every random choice follows from the seed so that renders can
+    // be reproduced. Resumed renders keep the seed of their checkpoint.
+    let seed = options
+        .seed
+        .or_else(|| checkpoint.as_ref().map(Checkpoint::seed))
+        .unwrap_or(0);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut scene = match (&options.scene_file, options.scene) {
+        (Some(path), _) => Scene::load(path)
+            .unwrap_or_else(|err| exit_with_error(&format!("{}: {}", path.display(), err))),
+        (None, Some(BuiltinScene::ThreeSpheres)) => Scene::three_spheres(),
+        (None, Some(BuiltinScene::CornellBox)) => Scene::cornell_box(),
+        (None, Some(BuiltinScene::CornellSmoke)) => Scene::cornell_smoke(),
+        (None, Some(BuiltinScene::Random)) | (None, None) => Scene::random(&mut rng),
+    };
+    options
+        .apply(&mut scene.image)
+        .unwrap_or_else(|err| exit_with_error(&err));
+    let mut settings = RenderSettings {
+        seed,
+        linear: options.linear,
+        progress: !options.quiet,
+        ..RenderSettings::default()
+    };
+    options
+        .apply_render(&mut settings, &scene.image)
+        .unwrap_or_else(|err| exit_with_error(&err));
+
+    let create = |path: &Path| {
+        BufWriter::new(
+            File::create(path)
+                .unwrap_or_else(|err| exit_with_error(&format!("{}: {}", path.display(), err))),
+        )
+    };
+    let mut output: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(create(path)),
+        None => Box::new(BufWriter::new(stdout())),
+    };
+    let heatmap = options
+        .heatmap
+        .as_ref()
+        .zip(heatmap_format)
+        .map(|(path, format)| (create(path), format));
+
+    // Cuts the current pass short once the tiles being rendered are done
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Ctrl-C stops the render within the current pass, so that what was
+    // rendered is still written. A second one exits at once.
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        let (interrupted, stop) = (interrupted.clone(), stop.clone());
+        ctrlc::set_handler(move || {
+            if interrupted.swap(true, Ordering::SeqCst) {
+                process::exit(130);
+            }
+            stop.store(true, Ordering::SeqCst);
+            eprintln!("interrupted, stopping after the tiles being rendered");
         })
-        .collect();
-
-    // Merge lines by sorting them in place
-    lines.par_sort_by(|a, b| b.0.cmp(&a.0));
-
-    //Render in PPM format
-    println!("P3\n{} {}\n255", image_width, image_height);
-    lines
-        .iter()
-        .progress()
-        .map(|t| &t.1)
-        .flatten()
-        .for_each(|pixel| write_color(&mut stdout(), &pixel, samples_per_pixel));
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    }
+
+    // Passes are also cut short every checkpoint interval for a checkpoint
+    // to be written, then carried on with
+    if options.checkpoint.is_some() {
+        let interval = options
+            .checkpoint_interval
+            .unwrap_or_else(|| Duration::from_secs(60));
+        let stop = stop.clone();
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            stop.store(true, Ordering::SeqCst);
+        });
+    }
+
+    // The pass running at the time limit is cut short too
+    let mut time_limit = options
+        .time_limit
+        .map(|limit| TimeLimit::new(Instant::now(), limit));
+    if let Some(time_limit) = &time_limit {
+        let (deadline, stop) = (time_limit.deadline(), stop.clone());
+        thread::spawn(move || {
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            stop.store(true, Ordering::SeqCst);
+        });
+    }
+    let mut renderer = match checkpoint {
+        Some(checkpoint) => Renderer::resume(scene, &settings, checkpoint)
+            .unwrap_or_else(|err| exit_with_error(&err)),
+        None => Renderer::new(scene, &settings),
+    };
+    let samples_per_pixel = renderer.samples_per_pixel();
+    let mut pass_start = Instant::now();
+    loop {
+        // Time limited renders keep adding samples until the time is up
+        if renderer.finished() {
+            if time_limit.is_none() {
+                break;
+            }
+            renderer.add_samples(renderer.samples_per_pixel() + samples_per_pixel);
+        }
+
+        let pass_done = renderer.render_pass_until(|| stop.load(Ordering::SeqCst));
+        stop.store(false, Ordering::SeqCst);
+        if let Some(path) = &options.checkpoint {
+            write_checkpoint(path, &renderer.checkpoint())
+                .unwrap_or_else(|err| exit_with_error(&format!("{}: {}", path.display(), err)));
+        }
+
+        if interrupted.load(Ordering::SeqCst) {
+            break;
+        }
+        if let Some(time_limit) = &mut time_limit {
+            if pass_done {
+                time_limit.pass_done(pass_start.elapsed());
+            }
+            if !time_limit.carry_on(Instant::now(), !pass_done) {
+                break;
+            }
+        }
+        if pass_done {
+            pass_start = Instant::now();
+        }
+    }
+    // Every pixel is the average of the samples it took, even when the render
+    // was interrupted
+    let image = renderer.image();
+    let sample_counts = renderer.sample_counts();
+
+    write_image(&mut output, &image, format)
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    if let Some((mut heatmap_output, heatmap_format)) = heatmap {
+        write_image(
+            &mut heatmap_output,
+            &sample_counts.heatmap(),
+            heatmap_format,
+        )
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    }
 }
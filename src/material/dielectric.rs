@@ -1,10 +1,9 @@
-use rand::Rng;
-
 use crate::color::Color;
 use crate::geometry::hittable::HitRecord;
 use crate::geometry::ray::Ray;
 use crate::geometry::Vec3;
-use crate::material::{Material, ScatteredRecord};
+use crate::material::{Material, Scatter, ScatteredRecord};
+use crate::sampler::Sampler;
 
 pub struct Dielectric {
     ir: f32, // Index of Refraction
@@ -27,7 +26,12 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatteredRecord> {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatteredRecord> {
         let refraction_ratio = if rec.front_face() {
             1.0 / self.ir
         } else {
@@ -40,21 +44,20 @@ impl Material for Dielectric {
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
 
-        let mut rng = rand::thread_rng();
         let direction = if cannot_refract
-            || Self::reflectance(cos_theta, refraction_ratio) > rng.gen::<f32>()
+            || Self::reflectance(cos_theta, refraction_ratio) > sampler.get_1d()
         {
             Vec3::reflect(&unit_direction, rec.normal())
         } else {
             Vec3::refract(&unit_direction, rec.normal(), refraction_ratio)
         };
 
-        let scattered = Ray::new(*rec.p(), direction);
+        let scattered = Ray::new(*rec.p(), direction, r_in.time);
         let attenuation = Color::new(1.0, 1.0, 1.0);
 
         Option::Some(ScatteredRecord {
             attenuation,
-            ray: scattered,
+            scatter: Scatter::Specular(scattered),
         })
     }
 }
@@ -0,0 +1,30 @@
+use crate::color::Color;
+use crate::geometry::hittable::HitRecord;
+use crate::geometry::ray::Ray;
+use crate::material::{Material, ScatteredRecord};
+use crate::sampler::Sampler;
+
+pub struct DiffuseLight {
+    pub emit: Color,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: &Color) -> Self {
+        Self { emit: *emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatteredRecord> {
+        Option::None
+    }
+
+    fn emitted(&self, _r_in: &Ray, _rec: &HitRecord) -> Color {
+        self.emit
+    }
+}
@@ -0,0 +1,45 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::geometry::hittable::HitRecord;
+use crate::geometry::ray::Ray;
+use crate::material::pdf::UniformSpherePdf;
+use crate::material::{Material, Scatter, ScatteredRecord};
+use crate::sampler::Sampler;
+use crate::texture::{SolidColor, Texture};
+
+// Phase function of participating media, scatters uniformly in all directions
+pub struct Isotropic {
+    pub albedo: Arc<dyn Texture + Send + Sync>,
+}
+
+impl Isotropic {
+    pub fn new(a: &Color) -> Self {
+        Self::textured(Arc::new(SolidColor::new(a)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Send + Sync>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatteredRecord> {
+        let (u, v) = rec.uv();
+
+        Option::Some(ScatteredRecord {
+            attenuation: self.albedo.value(u, v, rec.p()),
+            scatter: Scatter::Diffuse(Box::new(UniformSpherePdf)),
+        })
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+}
@@ -1,32 +1,46 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
 use crate::color::Color;
 use crate::geometry::hittable::HitRecord;
 use crate::geometry::ray::Ray;
 use crate::geometry::Vec3;
-use crate::material::{Material, ScatteredRecord};
+use crate::material::pdf::CosinePdf;
+use crate::material::{Material, Scatter, ScatteredRecord};
+use crate::sampler::Sampler;
+use crate::texture::{SolidColor, Texture};
 
 pub struct Lambertian {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture + Send + Sync>,
 }
 
 impl Lambertian {
     pub fn new(a: &Color) -> Self {
-        Self { albedo: *a }
+        Self::textured(Arc::new(SolidColor::new(a)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Send + Sync>) -> Self {
+        Self { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _r_in: &Ray, rec: &HitRecord) -> Option<ScatteredRecord> {
-        let mut scatter_direction = rec.normal() + Vec3::random_unit_vector();
-
-        // Catch degenerate scatter direction
-        if scatter_direction.near_zero() {
-            scatter_direction = *rec.normal()
-        }
-        let scattered = Ray::new(*rec.p(), scatter_direction);
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatteredRecord> {
+        let (u, v) = rec.uv();
 
         Option::Some(ScatteredRecord {
-            attenuation: self.albedo,
-            ray: scattered,
+            attenuation: self.albedo.value(u, v, rec.p()),
+            scatter: Scatter::Diffuse(Box::new(CosinePdf::new(rec.normal()))),
         })
     }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32 {
+        let cosine = Vec3::dot(rec.normal(), &Vec3::unit_vector(&scattered.dir));
+        cosine.max(0.0) / PI
+    }
 }
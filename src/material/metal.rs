@@ -1,34 +1,49 @@
+use std::sync::Arc;
+
 use crate::color::Color;
 use crate::geometry::hittable::HitRecord;
 use crate::geometry::ray::Ray;
 use crate::geometry::Vec3;
-use crate::material::{Material, ScatteredRecord};
+use crate::material::{Material, Scatter, ScatteredRecord};
+use crate::sampler::{unit_ball, Sampler};
+use crate::texture::{SolidColor, Texture};
 
 pub struct Metal {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture + Send + Sync>,
     pub fuzz: f32,
 }
 
 impl Metal {
     pub fn new(a: &Color, f: f32) -> Self {
+        Self::textured(Arc::new(SolidColor::new(a)), f)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Send + Sync>, f: f32) -> Self {
         let fuzz = if f < 1.0 { f } else { 1.0 };
 
-        Self { albedo: *a, fuzz }
+        Self { albedo, fuzz }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatteredRecord> {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatteredRecord> {
         let reflected = Vec3::reflect(&Vec3::unit_vector(&r_in.dir), rec.normal());
         let scattered = Ray::new(
             *rec.p(),
-            reflected + self.fuzz * Vec3::random_in_unit_sphere(),
+            reflected + self.fuzz * unit_ball(sampler.get_2d(), sampler.get_1d()),
+            r_in.time,
         );
 
         if Vec3::dot(&scattered.dir, rec.normal()) > 0.0 {
+            let (u, v) = rec.uv();
             Option::Some(ScatteredRecord {
-                attenuation: self.albedo,
-                ray: scattered,
+                attenuation: self.albedo.value(u, v, rec.p()),
+                scatter: Scatter::Specular(scattered),
             })
         } else {
             Option::None
@@ -1,16 +1,47 @@
+//! How surfaces and media scatter and emit light.
+
 use crate::color::Color;
 use crate::geometry::hittable::HitRecord;
 use crate::geometry::ray::Ray;
+use crate::material::pdf::Pdf;
+use crate::sampler::Sampler;
 
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod pdf;
+
+pub enum Scatter {
+    // Mirror and glass bounces, followed as they are
+    Specular(Ray),
+    // Directions drawn from a distribution, which the integrator weighs
+    // against the ones pointing towards lights
+    Diffuse(Box<dyn Pdf>),
+}
 
 pub struct ScatteredRecord {
     pub attenuation: Color,
-    pub ray: Ray,
+    pub scatter: Scatter,
 }
 
 pub trait Material {
-    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatteredRecord>;
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatteredRecord>;
+
+    // Density of diffuse scattering towards the scattered direction, the
+    // attenuation times this density is the BRDF times the cosine term
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f32 {
+        0.0
+    }
+
+    // Most materials do not emit light
+    fn emitted(&self, _r_in: &Ray, _rec: &HitRecord) -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
 }
@@ -0,0 +1,55 @@
+use std::f32::consts::PI;
+
+use crate::geometry::plane::tangent_basis;
+use crate::geometry::Vec3;
+use crate::sampler::{unit_sphere, Sampler};
+
+// Distribution of scattered directions
+pub trait Pdf {
+    // Density over solid angle
+    fn value(&self, direction: &Vec3) -> f32;
+    fn generate(&self, sampler: &mut dyn Sampler) -> Vec3;
+}
+
+// Directions around the normal, weighted by their cosine with it
+pub struct CosinePdf {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl CosinePdf {
+    pub fn new(normal: &Vec3) -> Self {
+        let w = Vec3::unit_vector(normal);
+        let (u, v) = tangent_basis(&w);
+
+        Self { u, v, w }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: &Vec3) -> f32 {
+        let cosine = Vec3::dot(&Vec3::unit_vector(direction), &self.w);
+        cosine.max(0.0) / PI
+    }
+
+    fn generate(&self, sampler: &mut dyn Sampler) -> Vec3 {
+        let (r1, r2) = sampler.get_2d();
+        let phi = 2.0 * PI * r1;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+
+        r2.sqrt() * (cos_phi * self.u + sin_phi * self.v) + (1.0 - r2).sqrt() * self.w
+    }
+}
+
+pub struct UniformSpherePdf;
+
+impl Pdf for UniformSpherePdf {
+    fn value(&self, _direction: &Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn generate(&self, sampler: &mut dyn Sampler) -> Vec3 {
+        unit_sphere(sampler.get_2d())
+    }
+}
@@ -0,0 +1,48 @@
+//! Rendered images.
+
+use std::slice::{Chunks, ChunksMut};
+
+use crate::color::Color;
+
+/// Linear radiance, stored row by row from the top of the image.
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Framebuffer {
+    /// Black image of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Framebuffer {
+            width,
+            height,
+            pixels: vec![Color::default(); width * height],
+        }
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixels row by row from the top of the image.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Rows of pixels from the top of the image.
+    pub fn rows(&self) -> Chunks<'_, Color> {
+        self.pixels.chunks(self.width)
+    }
+
+    /// Rows of pixels from the top of the image, to be modified.
+    pub fn rows_mut(&mut self) -> ChunksMut<'_, Color> {
+        self.pixels.chunks_mut(self.width)
+    }
+}
@@ -0,0 +1,19 @@
+use std::io::{self, Write};
+
+use image::codecs::hdr::HdrEncoder;
+use image::Rgb;
+
+use crate::output::framebuffer::Framebuffer;
+
+// Radiance RGBE
+pub fn write(w: &mut dyn Write, image: &Framebuffer) -> io::Result<()> {
+    let data: Vec<Rgb<f32>> = image
+        .pixels()
+        .iter()
+        .map(|pixel| Rgb([pixel.x.max(0.0), pixel.y.max(0.0), pixel.z.max(0.0)]))
+        .collect();
+
+    HdrEncoder::new(w)
+        .encode(&data, image.width(), image.height())
+        .map_err(io::Error::other)
+}
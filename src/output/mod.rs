@@ -0,0 +1,82 @@
+//! Image file formats.
+//!
+//! ```
+//! use raytracing::output::{write_image, OutputFormat};
+//! use raytracing::Framebuffer;
+//!
+//! let image = Framebuffer::new(4, 2);
+//! let mut ppm = vec![];
+//! write_image(&mut ppm, &image, OutputFormat::Ppm).unwrap();
+//! assert!(ppm.starts_with(b"P6"));
+//! ```
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::output::framebuffer::Framebuffer;
+
+pub mod framebuffer;
+mod hdr;
+mod pfm;
+mod png;
+mod ppm;
+
+/// Image file formats, the 8 and 16-bit ones are gamma corrected and clamped.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    /// Binary 8-bit PPM (P6).
+    Ppm,
+    /// 8-bit PNG.
+    Png,
+    /// 16-bit PNG.
+    Png16,
+    /// Portable float map of the unclamped radiance.
+    Pfm,
+    /// Radiance RGBE of the unclamped radiance.
+    Hdr,
+}
+
+impl OutputFormat {
+    /// Names of the formats on the command line.
+    pub const NAMES: &'static [&'static str] = &["ppm", "png", "png16", "pfm", "hdr"];
+
+    /// Format matching the extension of the path, if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Some(OutputFormat::Ppm),
+            "png" => Some(OutputFormat::Png),
+            "pfm" => Some(OutputFormat::Pfm),
+            "hdr" => Some(OutputFormat::Hdr),
+            _ => Option::None,
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(OutputFormat::Ppm),
+            "png" => Ok(OutputFormat::Png),
+            "png16" => Ok(OutputFormat::Png16),
+            "pfm" => Ok(OutputFormat::Pfm),
+            "hdr" => Ok(OutputFormat::Hdr),
+            _ => Err(format!("unknown output format '{}'", s)),
+        }
+    }
+}
+
+/// Writes the image in the format and flushes the writer.
+pub fn write_image(w: &mut dyn Write, image: &Framebuffer, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Ppm => ppm::write(w, image),
+        OutputFormat::Png => png::write(w, image, png::BitDepth::Eight),
+        OutputFormat::Png16 => png::write(w, image, png::BitDepth::Sixteen),
+        OutputFormat::Pfm => pfm::write(w, image),
+        OutputFormat::Hdr => hdr::write(w, image),
+    }?;
+    w.flush()
+}
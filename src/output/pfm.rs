@@ -0,0 +1,18 @@
+use std::io::{self, Write};
+
+use crate::output::framebuffer::Framebuffer;
+
+// Portable float map, rows are stored from the bottom of the image and a
+// negative scale stands for little endian floats
+pub fn write(w: &mut dyn Write, image: &Framebuffer) -> io::Result<()> {
+    write!(w, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+
+    let data: Vec<u8> = image
+        .rows()
+        .rev()
+        .flatten()
+        .flat_map(|pixel| [pixel.x, pixel.y, pixel.z])
+        .flat_map(f32::to_le_bytes)
+        .collect();
+    w.write_all(&data)
+}
@@ -0,0 +1,40 @@
+use std::io::{self, Write};
+
+use image::codecs::png::PngEncoder;
+use image::ColorType;
+
+use crate::color::{to_rgb16, to_rgb8};
+use crate::output::framebuffer::Framebuffer;
+
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+pub fn write(w: &mut dyn Write, image: &Framebuffer, depth: BitDepth) -> io::Result<()> {
+    let (data, color_type): (Vec<u8>, _) = match depth {
+        BitDepth::Eight => (
+            image.pixels().iter().flat_map(to_rgb8).collect(),
+            ColorType::Rgb8,
+        ),
+        // PNG samples are big endian
+        BitDepth::Sixteen => (
+            image
+                .pixels()
+                .iter()
+                .flat_map(to_rgb16)
+                .flat_map(u16::to_be_bytes)
+                .collect(),
+            ColorType::Rgb16,
+        ),
+    };
+
+    PngEncoder::new(w)
+        .encode(
+            &data,
+            image.width() as u32,
+            image.height() as u32,
+            color_type,
+        )
+        .map_err(io::Error::other)
+}
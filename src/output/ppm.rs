@@ -0,0 +1,11 @@
+use std::io::{self, Write};
+
+use crate::color::to_rgb8;
+use crate::output::framebuffer::Framebuffer;
+
+pub fn write(w: &mut dyn Write, image: &Framebuffer) -> io::Result<()> {
+    write!(w, "P6\n{} {}\n255\n", image.width(), image.height())?;
+
+    let data: Vec<u8> = image.pixels().iter().flat_map(to_rgb8).collect();
+    w.write_all(&data)
+}
@@ -0,0 +1,191 @@
+use std::io::{self, Read, Write};
+
+use crate::color::Color;
+use crate::film::FilmPixel;
+use crate::render::PixelStats;
+
+const MAGIC: &[u8; 8] = b"RTOWCKPT";
+const VERSION: u32 = 2;
+
+// Magic, version, size, seed, fingerprint and passes
+const HEADER_SIZE: usize = 8 + 4 + 2 * 8 + 2 * 8 + 4 * 4;
+// Film pixel, sample statistics and next sample
+const PIXEL_SIZE: usize = 4 * 4 + 4 + 2 * 8 + 4;
+
+/// The radiance accumulated by a render, the number of samples of every pixel
+/// and where their sequence stands, to carry on with the render later. It
+/// holds a fingerprint of the scene and image settings, a render can only be
+/// resumed with the same ones.
+///
+/// Samples only depend on the seed and on their pixel and index, so that a
+/// render resumed from a checkpoint takes the same samples as an
+/// uninterrupted one. Its pixels match up to rounding, as the samples
+/// reaching a pixel from several tiles may be summed in another order.
+///
+/// ```
+/// use raytracing::render::Checkpoint;
+/// use raytracing::{RenderSettings, Renderer, Scene};
+///
+/// let scene = || {
+///     let mut scene = Scene::three_spheres();
+///     scene.image.width = 16;
+///     scene.image.height = 9;
+///     scene.image.samples_per_pixel = 4;
+///     scene
+/// };
+/// let settings = RenderSettings {
+///     passes: 2,
+///     ..RenderSettings::default()
+/// };
+///
+/// let mut renderer = Renderer::new(scene(), &settings);
+/// renderer.render_pass();
+/// let mut file = vec![];
+/// renderer.checkpoint().write(&mut file).unwrap();
+///
+/// let checkpoint = Checkpoint::read(&mut file.as_slice()).unwrap();
+/// let mut resumed = Renderer::resume(scene(), &settings, checkpoint).unwrap();
+/// while resumed.render_pass() {}
+/// assert!(resumed.sample_counts().counts().iter().all(|&n| n == 4));
+/// ```
+pub struct Checkpoint {
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) seed: u64,
+    pub(super) fingerprint: u64,
+    pub(super) first_sample: u32,
+    pub(super) last_sample: u32,
+    pub(super) pass: u32,
+    pub(super) passes: u32,
+    // Row by row from the top of the image
+    pub(super) film: Vec<FilmPixel>,
+    pub(super) stats: Vec<PixelStats>,
+    pub(super) next_samples: Vec<u32>,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_bytes<const N: usize>(r: &mut dyn Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u32(r: &mut dyn Read) -> io::Result<u32> {
+    read_bytes(r).map(u32::from_le_bytes)
+}
+
+fn read_u64(r: &mut dyn Read) -> io::Result<u64> {
+    read_bytes(r).map(u64::from_le_bytes)
+}
+
+fn read_f32(r: &mut dyn Read) -> io::Result<f32> {
+    read_bytes(r).map(f32::from_le_bytes)
+}
+
+fn read_f64(r: &mut dyn Read) -> io::Result<f64> {
+    read_bytes(r).map(f64::from_le_bytes)
+}
+
+impl Checkpoint {
+    /// Seed of the render, which a resumed render must use too.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Writes a little endian header followed by the film pixels, sample
+    /// statistics and next sample of every pixel.
+    pub fn write(&self, w: &mut dyn Write) -> io::Result<()> {
+        let mut data = Vec::with_capacity(HEADER_SIZE + self.film.len() * PIXEL_SIZE);
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&(self.width as u64).to_le_bytes());
+        data.extend_from_slice(&(self.height as u64).to_le_bytes());
+        data.extend_from_slice(&self.seed.to_le_bytes());
+        data.extend_from_slice(&self.fingerprint.to_le_bytes());
+        for value in &[self.first_sample, self.last_sample, self.pass, self.passes] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let pixels = self.film.iter().zip(&self.stats);
+        for ((pixel, stats), next_sample) in pixels.zip(&self.next_samples) {
+            let sum = pixel.weighted_sum;
+            for value in &[sum.x, sum.y, sum.z, pixel.weight] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.extend_from_slice(&stats.count.to_le_bytes());
+            data.extend_from_slice(&stats.mean.to_le_bytes());
+            data.extend_from_slice(&stats.m2.to_le_bytes());
+            data.extend_from_slice(&next_sample.to_le_bytes());
+        }
+
+        w.write_all(&data)?;
+        w.flush()
+    }
+
+    /// Reads a checkpoint written by [`Checkpoint::write`].
+    pub fn read(r: &mut dyn Read) -> io::Result<Self> {
+        if &read_bytes::<8>(r)? != MAGIC {
+            return Err(invalid_data("not a checkpoint"));
+        }
+        let version = read_u32(r)?;
+        if version != VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported checkpoint version {}",
+                version
+            )));
+        }
+
+        let width = read_u64(r)? as usize;
+        let height = read_u64(r)? as usize;
+        let seed = read_u64(r)?;
+        let fingerprint = read_u64(r)?;
+        let first_sample = read_u32(r)?;
+        let last_sample = read_u32(r)?;
+        let pass = read_u32(r)?;
+        let passes = read_u32(r)?;
+        if first_sample > last_sample || pass > passes || passes == 0 {
+            return Err(invalid_data("inconsistent sample passes"));
+        }
+
+        let pixel_count = width
+            .checked_mul(height)
+            .ok_or_else(|| invalid_data("image size overflows"))?;
+        let mut film = Vec::new();
+        let mut stats = Vec::new();
+        let mut next_samples = Vec::new();
+        for _ in 0..pixel_count {
+            let sum = Color::new(read_f32(r)?, read_f32(r)?, read_f32(r)?);
+            film.push(FilmPixel {
+                weighted_sum: sum,
+                weight: read_f32(r)?,
+            });
+            stats.push(PixelStats {
+                count: read_u32(r)?,
+                mean: read_f64(r)?,
+                m2: read_f64(r)?,
+            });
+            let next_sample = read_u32(r)?;
+            if next_sample > last_sample {
+                return Err(invalid_data("inconsistent sample passes"));
+            }
+            next_samples.push(next_sample);
+        }
+
+        Ok(Checkpoint {
+            width,
+            height,
+            seed,
+            fingerprint,
+            first_sample,
+            last_sample,
+            pass,
+            passes,
+            film,
+            stats,
+            next_samples,
+        })
+    }
+}
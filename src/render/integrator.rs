@@ -0,0 +1,136 @@
+use crate::color::Color;
+use crate::environment::Environment;
+use crate::geometry::hittable::{HitRange, HitRecord, Hittable};
+use crate::geometry::ray::Ray;
+use crate::material::pdf::Pdf;
+use crate::material::Scatter;
+use crate::sampler::Sampler;
+
+// Power heuristic weight of a sampling strategy against the other one
+fn mis_weight(pdf: f32, other_pdf: f32) -> f32 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    a / (a + b)
+}
+
+// Next event estimation: light arriving straight from a point drawn on one
+// of the lights, weighed against drawing the same direction from the BSDF
+fn sample_light(
+    r: &Ray,
+    rec: &HitRecord,
+    attenuation: &Color,
+    bsdf: &dyn Pdf,
+    world: &dyn Hittable,
+    lights: &dyn Hittable,
+    sampler: &mut dyn Sampler,
+) -> Color {
+    let direction = lights.random_direction(rec.p(), sampler);
+    let light_pdf = lights.pdf_value(rec.p(), &direction);
+    if light_pdf <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    let shadow_ray = Ray::new(*rec.p(), direction, r.time).with_sample(sampler);
+    let scattering_pdf = rec.material().scattering_pdf(r, rec, &shadow_ray);
+    if scattering_pdf <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    // Anything in front of the light blocks it
+    let light_rec = match lights.hit(&shadow_ray, HitRange::new(0.001, f32::INFINITY)) {
+        Some(light_rec) => light_rec,
+        None => return Color::new(0.0, 0.0, 0.0),
+    };
+    let occluder_range = HitRange::new(0.001, light_rec.t() * (1.0 - 1e-4));
+    if world.hit(&shadow_ray, occluder_range).is_some() {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    let emitted = light_rec.material().emitted(&shadow_ray, &light_rec);
+    mis_weight(light_pdf, bsdf.value(&direction)) * scattering_pdf / light_pdf
+        * (*attenuation * emitted)
+}
+
+// Iterative path tracer. Paths longer than min_depth bounces are ended at
+// random by Russian roulette, surviving ones are boosted to keep the estimate
+// unbiased. The optional max_depth cuts paths off for good, darkening the
+// image.
+pub fn ray_color(
+    mut r: Ray,
+    environment: &dyn Environment,
+    world: &dyn Hittable,
+    lights: &dyn Hittable,
+    min_depth: u32,
+    max_depth: Option<u32>,
+    sampler: &mut dyn Sampler,
+) -> Color {
+    let mut radiance = Color::new(0.0, 0.0, 0.0);
+    let mut throughput = Color::new(1.0, 1.0, 1.0);
+
+    // Density the current ray was drawn with at a diffuse surface, None for
+    // camera rays and specular bounces whose hits on lights count in full
+    let mut bsdf_pdf: Option<f32> = Option::None;
+
+    for depth in 0u32.. {
+        if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            break;
+        }
+
+        let rec = match world.hit(&r, HitRange::new(0.001, f32::INFINITY)) {
+            Some(rec) => rec,
+            None => return radiance + throughput * environment.color(&r.dir),
+        };
+
+        let mut emitted = rec.material().emitted(&r, &rec);
+        if let Some(bsdf_pdf) = bsdf_pdf {
+            if emitted != Color::new(0.0, 0.0, 0.0) {
+                emitted = mis_weight(bsdf_pdf, lights.pdf_value(&r.orig, &r.dir)) * emitted;
+            }
+        }
+        radiance = radiance + throughput * emitted;
+
+        let scatter_record = match rec.material().scatter(&r, &rec, sampler) {
+            Some(scatter_record) => scatter_record,
+            None => break,
+        };
+        let attenuation = scatter_record.attenuation;
+
+        let scattered = match scatter_record.scatter {
+            Scatter::Specular(scattered) => {
+                throughput = throughput * attenuation;
+                bsdf_pdf = Option::None;
+                scattered.with_sample(sampler)
+            }
+            Scatter::Diffuse(pdf) => {
+                let direct =
+                    sample_light(&r, &rec, &attenuation, pdf.as_ref(), world, lights, sampler);
+                radiance = radiance + throughput * direct;
+
+                let scattered =
+                    Ray::new(*rec.p(), pdf.generate(sampler), r.time).with_sample(sampler);
+                let pdf_value = pdf.value(&scattered.dir);
+                if pdf_value <= 0.0 {
+                    break;
+                }
+
+                let scattering_pdf = rec.material().scattering_pdf(&r, &rec, &scattered);
+                throughput = throughput * (scattering_pdf / pdf_value * attenuation);
+                bsdf_pdf = Option::Some(pdf_value);
+                scattered
+            }
+        };
+
+        if depth >= min_depth {
+            // Never certain to survive, so even lossless paths trapped in
+            // glass end eventually
+            let survival = throughput.x.max(throughput.y).max(throughput.z).min(0.95);
+            if sampler.get_1d() >= survival {
+                break;
+            }
+            throughput = throughput / survival;
+        }
+
+        r = scattered;
+    }
+
+    radiance
+}
@@ -0,0 +1,710 @@
+//! The path tracer.
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use indicatif::ProgressBar;
+
+use crate::color::Color;
+use crate::environment::Environment;
+use crate::film::{Film, FilmTile};
+use crate::geometry::bvh::Bvh;
+use crate::geometry::camera::Camera;
+use crate::geometry::hittable::{Hittable, HittableList};
+use crate::output::framebuffer::Framebuffer;
+use crate::sampler::hash;
+use crate::scene::{ImageSettings, Scene};
+
+use self::integrator::ray_color;
+use self::tiles::{tiles, Tile};
+
+pub use self::checkpoint::Checkpoint;
+pub use self::tiles::TileOrder;
+pub use self::time_limit::TimeLimit;
+
+mod checkpoint;
+mod integrator;
+mod tiles;
+mod time_limit;
+
+/// How a scene is rendered, as opposed to what the image looks like which is
+/// part of the scene.
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    /// Seed of every random choice, renders of a scene with the same seed
+    /// are identical whatever the number of threads.
+    pub seed: u64,
+    /// Tests every object for every ray instead of using a BVH, to cross-check
+    /// it.
+    pub linear: bool,
+    /// Shows a progress bar on the terminal.
+    pub progress: bool,
+    /// Side of the square tiles the image is split into, in pixels.
+    pub tile_size: usize,
+    /// Order tiles are handed out to threads in.
+    pub tile_order: TileOrder,
+    /// Number of passes over the whole image the samples of every pixel are
+    /// spread over, at most one per sample.
+    pub passes: u32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            seed: 0,
+            linear: false,
+            progress: false,
+            tile_size: 16,
+            tile_order: TileOrder::Hilbert,
+            passes: 1,
+        }
+    }
+}
+
+// Running mean and variance of the luminance of the samples of a pixel
+#[derive(Default, Clone)]
+struct PixelStats {
+    count: u32,
+    mean: f64,
+    m2: f64,
+}
+
+impl PixelStats {
+    // Welford's online algorithm
+    fn add(&mut self, color: &Color) {
+        let luminance = (0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z) as f64;
+        self.count += 1;
+        let delta = luminance - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (luminance - self.mean);
+    }
+
+    // Whether the standard error of the mean is under the threshold, relative
+    // to the mean but not below an absolute floor for dark pixels
+    fn converged(&self, threshold: f32) -> bool {
+        if self.count < 2 {
+            return false;
+        }
+
+        let n = self.count as f64;
+        let standard_error = (self.m2 / ((n - 1.0) * n)).sqrt();
+        standard_error <= threshold as f64 * self.mean.abs().max(0.01)
+    }
+}
+
+/// Number of samples taken in every pixel, row by row from the top of the
+/// image.
+pub struct SampleCounts {
+    width: usize,
+    height: usize,
+    counts: Vec<u32>,
+}
+
+impl SampleCounts {
+    /// Samples taken by every pixel, row by row from the top.
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Sample counts from blue for the fewest to red for the most.
+    pub fn heatmap(&self) -> Framebuffer {
+        let min = self.counts.iter().copied().min().unwrap_or(0);
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        let range = (max - min).max(1) as f32;
+
+        let mut image = Framebuffer::new(self.width, self.height);
+        image
+            .rows_mut()
+            .flatten()
+            .zip(&self.counts)
+            .for_each(|(pixel, &count)| {
+                let t = (count - min) as f32 / range;
+                *pixel = Color::new(t, 0.0, 1.0 - t);
+            });
+        image
+    }
+}
+
+// Pixels of a tile and the statistics of their samples over the passes
+struct TileState {
+    tile: Tile,
+    stats: Vec<PixelStats>,
+    // First sample of every pixel yet to be taken, or skipped by adaptive
+    // sampling
+    next_samples: Vec<u32>,
+}
+
+// Fingerprint of the scene and of the image settings the samples depend on,
+// but for the image size and the samples per pixel which may be raised
+fn fingerprint(scene: &Scene) -> u64 {
+    let image = &scene.image;
+    hash(&[
+        scene.source,
+        image.min_samples as u64,
+        image
+            .noise_threshold
+            .map_or(u64::MAX, |threshold| threshold.to_bits() as u64),
+        image.min_depth as u64,
+        image.max_depth.map_or(u64::MAX, u64::from),
+        image.sampler as u64,
+        image.filter as u64,
+        image.filter_radius.to_bits() as u64,
+    ])
+}
+
+/// Renders a scene in progressive passes, every pass adding samples to the
+/// whole image.
+///
+/// ```
+/// use raytracing::{RenderSettings, Renderer, Scene};
+///
+/// let mut scene = Scene::three_spheres();
+/// scene.image.width = 16;
+/// scene.image.height = 9;
+/// scene.image.samples_per_pixel = 4;
+///
+/// let settings = RenderSettings {
+///     passes: 4,
+///     ..RenderSettings::default()
+/// };
+/// let mut renderer = Renderer::new(scene, &settings);
+/// while renderer.render_pass() {
+///     let preview = renderer.image();
+///     assert_eq!((preview.width(), preview.height()), (16, 9));
+/// }
+/// assert_eq!(renderer.pass(), 4);
+/// ```
+pub struct Renderer {
+    image: ImageSettings,
+    seed: u64,
+    fingerprint: u64,
+    camera: Camera,
+    environment: Box<dyn Environment + Send + Sync>,
+    world: Box<dyn Hittable + Sync + Send>,
+    lights: HittableList<'static>,
+    film: Film,
+    // Tiles in the order they are handed out to threads
+    tiles: Vec<TileState>,
+    // The samples from first_sample to last_sample are a batch taken in
+    // passes, the ones added by raising the samples per pixel are another
+    // batch taken in later passes
+    first_sample: u32,
+    last_sample: u32,
+    pass: u32,
+    passes: u32,
+    max_passes: u32,
+    progress_bar: ProgressBar,
+}
+
+impl Renderer {
+    /// A renderer of the scene that has not taken any sample yet.
+    pub fn new(scene: Scene, settings: &RenderSettings) -> Self {
+        let camera = scene.camera();
+        let fingerprint = fingerprint(&scene);
+        let image = scene.image;
+
+        // World, the linear list can be requested to cross-check the BVH
+        let world: Box<dyn Hittable + Sync + Send> = if settings.linear {
+            Box::new(scene.world)
+        } else {
+            Box::new(Bvh::new(scene.world))
+        };
+
+        let film = Film::new(
+            image.width,
+            image.height,
+            image.filter.build(image.filter_radius),
+        );
+
+        let tiles: Vec<TileState> = tiles(
+            image.width,
+            image.height,
+            settings.tile_size,
+            settings.tile_order,
+        )
+        .into_iter()
+        .map(|tile| {
+            let pixel_count = tile.columns.len() * tile.rows.len();
+            TileState {
+                tile,
+                stats: vec![PixelStats::default(); pixel_count],
+                next_samples: vec![0; pixel_count],
+            }
+        })
+        .collect();
+
+        let samples_per_pixel = image.samples_per_pixel;
+        let passes = settings.passes.min(samples_per_pixel).max(1);
+        let progress_bar = if settings.progress {
+            ProgressBar::new(tiles.len() as u64 * passes as u64)
+        } else {
+            ProgressBar::hidden()
+        };
+
+        Renderer {
+            image,
+            seed: settings.seed,
+            fingerprint,
+            camera,
+            environment: scene.environment,
+            world,
+            lights: scene.lights,
+            film,
+            tiles,
+            first_sample: 0,
+            last_sample: samples_per_pixel,
+            pass: 0,
+            passes,
+            max_passes: settings.passes,
+            progress_bar,
+        }
+    }
+
+    /// The renderer seeing the scene through another camera than the one of
+    /// its settings.
+    pub fn with_camera(self, camera: Camera) -> Self {
+        Renderer { camera, ..self }
+    }
+
+    /// Carries on with the render saved in the checkpoint, which must be of
+    /// the same scene and settings. The samples per pixel of the scene may be
+    /// raised to add samples to the checkpoint.
+    pub fn resume(
+        scene: Scene,
+        settings: &RenderSettings,
+        checkpoint: Checkpoint,
+    ) -> Result<Self, String> {
+        let mut renderer = Renderer::new(scene, settings);
+        let (width, height) = (renderer.image.width, renderer.image.height);
+        if (checkpoint.width, checkpoint.height) != (width, height) {
+            return Err(format!(
+                "the checkpoint is {}x{}, not {}x{}",
+                checkpoint.width, checkpoint.height, width, height
+            ));
+        }
+        if checkpoint.seed != renderer.seed {
+            return Err(format!(
+                "the checkpoint was rendered with seed {}",
+                checkpoint.seed
+            ));
+        }
+        if checkpoint.fingerprint != renderer.fingerprint {
+            return Err(
+                "the checkpoint was rendered from another scene or with other image settings"
+                    .to_string(),
+            );
+        }
+
+        renderer.film.pixels_mut().copy_from_slice(&checkpoint.film);
+        for state in &mut renderer.tiles {
+            let pixels = state.tile.pixels().zip(&mut state.stats);
+            for (((x, y), stats), next_sample) in pixels.zip(&mut state.next_samples) {
+                *stats = checkpoint.stats[y * width + x].clone();
+                *next_sample = checkpoint.next_samples[y * width + x];
+            }
+        }
+
+        renderer.first_sample = checkpoint.first_sample;
+        renderer.last_sample = checkpoint.last_sample;
+        renderer.pass = checkpoint.pass;
+        renderer.passes = checkpoint.passes;
+        renderer.image.samples_per_pixel =
+            renderer.image.samples_per_pixel.max(checkpoint.last_sample);
+
+        // Tiles already through the pass the checkpoint was taken in
+        let pass_end = renderer.pass_samples(renderer.pass).end;
+        let tiles_done = renderer
+            .tiles
+            .iter()
+            .filter(|state| state.next_samples.iter().all(|&next| next >= pass_end))
+            .count() as u64;
+        let tile_count = renderer.tiles.len() as u64;
+        if renderer.pass < renderer.passes {
+            renderer
+                .progress_bar
+                .set_position(tile_count * renderer.pass as u64 + tiles_done);
+        }
+        renderer
+            .progress_bar
+            .set_length(tile_count * renderer.passes as u64);
+        Ok(renderer)
+    }
+
+    /// Everything the render has accumulated so far, to resume it later.
+    pub fn checkpoint(&self) -> Checkpoint {
+        let width = self.image.width;
+        let mut stats = vec![PixelStats::default(); width * self.image.height];
+        let mut next_samples = vec![0; width * self.image.height];
+        for state in &self.tiles {
+            let pixels = state.tile.pixels().zip(&state.stats);
+            for (((x, y), pixel_stats), &next_sample) in pixels.zip(&state.next_samples) {
+                stats[y * width + x] = pixel_stats.clone();
+                next_samples[y * width + x] = next_sample;
+            }
+        }
+
+        Checkpoint {
+            width,
+            height: self.image.height,
+            seed: self.seed,
+            fingerprint: self.fingerprint,
+            first_sample: self.first_sample,
+            last_sample: self.last_sample,
+            pass: self.pass,
+            passes: self.passes,
+            film: self.film.pixels().to_vec(),
+            stats,
+            next_samples,
+        }
+    }
+
+    /// Number of passes rendered so far.
+    pub fn pass(&self) -> u32 {
+        self.pass
+    }
+
+    /// Passes the samples of the current segment are split into.
+    pub fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    // Samples of every pixel taken in a pass over the current batch, pixels
+    // take the same samples whatever the number of passes
+    fn pass_samples(&self, pass: u32) -> Range<u32> {
+        let (first_sample, passes) = (self.first_sample, self.passes as u64);
+        let sample_count = (self.last_sample - first_sample) as u64;
+        let sample_range = |pass: u32| first_sample + (sample_count * pass as u64 / passes) as u32;
+        sample_range(pass)..sample_range(pass + 1)
+    }
+
+    /// Renders the next pass over the image, returns false once every pass
+    /// has been rendered.
+    pub fn render_pass(&mut self) -> bool {
+        self.render_pass_until(|| false)
+    }
+
+    /// Renders the next pass over the image, or what is left of it, until
+    /// `stop` returns true. It is called before every tile is started, tiles
+    /// started by then are finished and the rest of the pass is left to the
+    /// next call. Returns whether the pass was rendered to its end, false
+    /// once every pass has been rendered or when stopped.
+    pub fn render_pass_until(&mut self, stop: impl Fn() -> bool + Sync) -> bool {
+        let samples_per_pixel = self.image.samples_per_pixel;
+        if self.finished() {
+            return false;
+        }
+        if self.pass == self.passes {
+            // Samples were added after the last pass
+            self.first_sample = self.last_sample;
+            self.last_sample = samples_per_pixel;
+            self.pass = 0;
+            self.passes = self
+                .max_passes
+                .min(self.last_sample - self.first_sample)
+                .max(1);
+            self.progress_bar
+                .set_length(self.tiles.len() as u64 * self.passes as u64);
+            self.progress_bar.set_position(0);
+        }
+
+        let samples = self.pass_samples(self.pass);
+
+        // The samples added to a render are a batch of their own, spread
+        // over the sample space independently of the earlier ones
+        let first_sample = self.first_sample;
+        let batch_size = self.last_sample - first_sample;
+        let batch_seed = if first_sample == 0 {
+            self.seed
+        } else {
+            hash(&[self.seed, first_sample as u64])
+        };
+
+        let Renderer {
+            ref image,
+            ref camera,
+            ref environment,
+            ref world,
+            ref lights,
+            ref mut film,
+            ref mut tiles,
+            ref progress_bar,
+            ..
+        } = *self;
+        let min_samples = image.min_samples.min(samples_per_pixel);
+        let (image_width, image_height) = (image.width, image.height);
+
+        // Idle threads take the next tile in order. Tiles reach the film
+        // pixels around them, so they are merged into the film in order too,
+        // those done early waiting for the ones before them, for its sums not
+        // to depend on the threads.
+        let tile_count = tiles.len();
+        let next_tile = AtomicUsize::new(0);
+        let tiles_done = AtomicUsize::new(0);
+        let tiles: Vec<Mutex<&mut TileState>> = tiles.iter_mut().map(Mutex::new).collect();
+        let merged = Mutex::new(MergeQueue {
+            film,
+            next: 0,
+            done: (0..tile_count).map(|_| Option::None).collect(),
+        });
+        rayon::scope(|scope| {
+            for _ in 0..rayon::current_num_threads().min(tile_count) {
+                scope.spawn(|_| loop {
+                    if stop() {
+                        break;
+                    }
+                    let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                    if index >= tile_count {
+                        break;
+                    }
+                    let mut state = tiles[index].lock().unwrap();
+                    let state = &mut **state;
+
+                    // The values of every pixel only depend on the seed and
+                    // not on the thread rendering it
+                    let mut sampler = image.sampler.build(batch_size, batch_seed);
+                    let mut film_tile = merged
+                        .lock()
+                        .unwrap()
+                        .film
+                        .tile(state.tile.columns.clone(), state.tile.rows.clone());
+
+                    let pending = state.next_samples.iter().any(|&next| next < samples.end);
+                    let pixels = state.tile.pixels().zip(&mut state.stats);
+                    for (((x, y), stats), next_sample) in pixels.zip(&mut state.next_samples) {
+                        for sample in samples.start.max(*next_sample)..samples.end {
+                            // Pixels stop being sampled once they are known
+                            // well enough
+                            if let Some(threshold) = image.noise_threshold {
+                                if sample >= min_samples && stats.converged(threshold) {
+                                    break;
+                                }
+                            }
+
+                            sampler.start_pixel_sample((x, y), sample - first_sample);
+                            let (dx, dy) = sampler.get_2d();
+                            let position = (x as f32 + dx, y as f32 + dy);
+
+                            // The camera looks at the image from its bottom
+                            // left corner
+                            let u = position.0 / image_width as f32;
+                            let v = 1.0 - position.1 / image_height as f32;
+
+                            let r = camera.get_ray(u, v, sampler.as_mut());
+                            let color = ray_color(
+                                r,
+                                environment.as_ref(),
+                                world.as_ref(),
+                                lights,
+                                image.min_depth,
+                                image.max_depth,
+                                sampler.as_mut(),
+                            );
+                            stats.add(&color);
+                            film_tile.add_sample(position, &color);
+                        }
+                        *next_sample = samples.end;
+                    }
+
+                    merged.lock().unwrap().push(index, film_tile);
+                    if pending {
+                        progress_bar.inc(1);
+                    }
+                    tiles_done.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        });
+
+        if tiles_done.into_inner() < tile_count {
+            return false;
+        }
+        self.pass += 1;
+        if self.finished() {
+            self.progress_bar.finish();
+        }
+        true
+    }
+
+    /// Samples every pixel takes at most once all passes are done.
+    pub fn samples_per_pixel(&self) -> u32 {
+        self.image.samples_per_pixel
+    }
+
+    /// Whether every sample of every pixel has been taken.
+    pub fn finished(&self) -> bool {
+        self.pass == self.passes && self.last_sample >= self.image.samples_per_pixel
+    }
+
+    /// Raises the number of samples of every pixel, the samples added are
+    /// taken by the next passes. They are spread over the sample space as a
+    /// batch of their own, well distributed among themselves but not with
+    /// the earlier samples.
+    pub fn add_samples(&mut self, samples_per_pixel: u32) {
+        self.image.samples_per_pixel = self.image.samples_per_pixel.max(samples_per_pixel);
+    }
+
+    /// Linear radiance of the samples taken so far.
+    pub fn image(&self) -> Framebuffer {
+        self.film.to_framebuffer()
+    }
+
+    /// Samples every pixel has taken so far.
+    pub fn sample_counts(&self) -> SampleCounts {
+        let width = self.image.width;
+        let mut counts = vec![0; width * self.image.height];
+        for state in &self.tiles {
+            for ((x, y), stats) in state.tile.pixels().zip(&state.stats) {
+                counts[y * width + x] = stats.count;
+            }
+        }
+
+        SampleCounts {
+            width,
+            height: self.image.height,
+            counts,
+        }
+    }
+}
+
+// Film tiles rendered out of order, waiting for the ones before them to be
+// merged into the film
+struct MergeQueue<'a> {
+    film: &'a mut Film,
+    next: usize,
+    done: Vec<Option<FilmTile>>,
+}
+
+impl MergeQueue<'_> {
+    fn push(&mut self, index: usize, tile: FilmTile) {
+        self.done[index] = Some(tile);
+        while let Some(tile) = self.done.get_mut(self.next).and_then(Option::take) {
+            self.film.merge(tile);
+            self.next += 1;
+        }
+    }
+}
+
+/// Renders the scene into linear radiance, on the rayon thread pool.
+///
+/// ```
+/// use raytracing::{render, RenderSettings, Scene};
+///
+/// let mut scene = Scene::three_spheres();
+/// scene.image.width = 16;
+/// scene.image.height = 9;
+/// scene.image.samples_per_pixel = 2;
+///
+/// let image = render(scene, &RenderSettings::default());
+/// assert_eq!((image.width(), image.height()), (16, 9));
+/// ```
+pub fn render(scene: Scene, settings: &RenderSettings) -> Framebuffer {
+    render_with_sample_counts(scene, settings).0
+}
+
+/// Renders the scene, also returning the number of samples every pixel took
+/// when sampling is adaptive.
+///
+/// ```
+/// use raytracing::render::render_with_sample_counts;
+/// use raytracing::{RenderSettings, Scene};
+///
+/// let mut scene = Scene::three_spheres();
+/// scene.image.width = 16;
+/// scene.image.height = 9;
+/// scene.image.samples_per_pixel = 64;
+/// scene.image.min_samples = 4;
+/// scene.image.noise_threshold = Some(0.05);
+///
+/// let (_, sample_counts) = render_with_sample_counts(scene, &RenderSettings::default());
+/// assert!(sample_counts.counts().iter().all(|&n| (4..=64).contains(&n)));
+/// ```
+pub fn render_with_sample_counts(
+    scene: Scene,
+    settings: &RenderSettings,
+) -> (Framebuffer, SampleCounts) {
+    let mut renderer = Renderer::new(scene, settings);
+    while renderer.render_pass() {}
+    (renderer.image(), renderer.sample_counts())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::SamplerKind;
+
+    fn scene() -> Scene {
+        let mut scene = Scene::three_spheres();
+        scene.image.width = 16;
+        scene.image.height = 9;
+        scene.image.samples_per_pixel = 4;
+        scene
+    }
+
+    #[test]
+    fn resume_rejects_other_image_settings() {
+        let settings = RenderSettings::default();
+        let mut renderer = Renderer::new(scene(), &settings);
+        renderer.render_pass();
+
+        let mut other = scene();
+        other.image.sampler = SamplerKind::Halton;
+        assert!(Renderer::resume(other, &settings, renderer.checkpoint()).is_err());
+        assert!(Renderer::resume(Scene::cornell_box(), &settings, renderer.checkpoint()).is_err());
+        assert!(Renderer::resume(scene(), &settings, renderer.checkpoint()).is_ok());
+    }
+
+    // Stops a pass once the given number of tiles have been started
+    fn after_tiles(count: usize) -> impl Fn() -> bool + Sync {
+        let started = AtomicUsize::new(0);
+        move || started.fetch_add(1, Ordering::SeqCst) >= count
+    }
+
+    #[test]
+    fn stopped_render_keeps_the_tiles_it_completed() {
+        let settings = RenderSettings {
+            tile_size: 4,
+            tile_order: TileOrder::Scanline,
+            ..RenderSettings::default()
+        };
+        let full = render(scene(), &settings);
+        let mut renderer = Renderer::new(scene(), &settings);
+        assert!(!renderer.render_pass_until(after_tiles(3)));
+        assert_eq!(renderer.pass(), 0);
+
+        // The first three tiles of the top row, every pixel of which is the
+        // average of its own samples
+        let image = renderer.image();
+        let counts = renderer.sample_counts();
+        for y in 0..9 {
+            for x in 0..16 {
+                let i = y * 16 + x;
+                if x < 12 && y < 4 {
+                    assert_eq!(counts.counts()[i], 4);
+                    assert_eq!(image.pixels()[i], full.pixels()[i]);
+                } else {
+                    assert_eq!(counts.counts()[i], 0);
+                    assert_eq!(image.pixels()[i], Color::new(0.0, 0.0, 0.0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stopped_pass_is_carried_on_with() {
+        let settings = RenderSettings {
+            tile_size: 4,
+            ..RenderSettings::default()
+        };
+        let full = render(scene(), &settings);
+        let mut renderer = Renderer::new(scene(), &settings);
+        assert!(!renderer.render_pass_until(|| true));
+        assert!(!renderer.render_pass_until(after_tiles(5)));
+
+        let checkpoint = renderer.checkpoint();
+        let mut resumed = Renderer::resume(scene(), &settings, checkpoint).unwrap();
+        assert!(resumed.render_pass());
+        assert!(resumed.finished());
+        assert!(resumed.sample_counts().counts().iter().all(|&n| n == 4));
+        assert_eq!(resumed.image().pixels(), full.pixels());
+    }
+}
@@ -0,0 +1,165 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Order tiles are handed out to threads in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TileOrder {
+    /// Row by row from the top of the image.
+    Scanline,
+    /// From the center of the image outwards, where the subject usually is.
+    Spiral,
+    /// Along a Hilbert curve, neighbouring tiles are rendered close in time
+    /// and share more of the scene in caches.
+    Hilbert,
+}
+
+impl TileOrder {
+    /// Names of the orders on the command line.
+    pub const NAMES: &'static [&'static str] = &["scanline", "spiral", "hilbert"];
+}
+
+impl FromStr for TileOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scanline" => Ok(TileOrder::Scanline),
+            "spiral" => Ok(TileOrder::Spiral),
+            "hilbert" => Ok(TileOrder::Hilbert),
+            _ => Err(format!("unknown tile order '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tile {
+    pub columns: Range<usize>,
+    pub rows: Range<usize>,
+}
+
+impl Tile {
+    // Pixels of the tile row by row
+    pub fn pixels(&self) -> impl Iterator<Item = (usize, usize)> {
+        let columns = self.columns.clone();
+        self.rows
+            .clone()
+            .flat_map(move |y| columns.clone().map(move |x| (x, y)))
+    }
+}
+
+// Distance of (x, y) along the Hilbert curve filling a square of side n, a
+// power of two
+fn hilbert_index(n: usize, mut x: usize, mut y: usize) -> usize {
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as usize;
+        let ry = (y & s > 0) as usize;
+        d += s * s * ((3 * rx) ^ ry);
+
+        // Rotate the quadrant so that the curve inside it starts and ends
+        // next to the neighbouring quadrants
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+// Square tiles covering the image, smaller ones along the right and bottom
+// edges, in the given order
+pub fn tiles(width: usize, height: usize, size: usize, order: TileOrder) -> Vec<Tile> {
+    let size = size.max(1);
+    let (tiles_x, tiles_y) = (width.div_ceil(size), height.div_ceil(size));
+
+    let mut coordinates: Vec<(usize, usize)> = (0..tiles_y)
+        .flat_map(|ty| (0..tiles_x).map(move |tx| (tx, ty)))
+        .collect();
+    match order {
+        TileOrder::Scanline => (),
+        TileOrder::Spiral => {
+            let center = ((tiles_x as f32 - 1.0) / 2.0, (tiles_y as f32 - 1.0) / 2.0);
+            let key = |&(tx, ty): &(usize, usize)| {
+                let (dx, dy) = (tx as f32 - center.0, ty as f32 - center.1);
+                (dx.abs().max(dy.abs()), dy.atan2(dx))
+            };
+            coordinates.sort_by(|a, b| {
+                let (ring_a, angle_a) = key(a);
+                let (ring_b, angle_b) = key(b);
+                ring_a.total_cmp(&ring_b).then(angle_a.total_cmp(&angle_b))
+            });
+        }
+        TileOrder::Hilbert => {
+            let n = tiles_x.max(tiles_y).next_power_of_two();
+            coordinates.sort_by_key(|&(tx, ty)| hilbert_index(n, tx, ty));
+        }
+    }
+
+    coordinates
+        .into_iter()
+        .map(|(tx, ty)| Tile {
+            columns: tx * size..((tx + 1) * size).min(width),
+            rows: ty * size..((ty + 1) * size).min(height),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_every_pixel_once() {
+        for order in [TileOrder::Scanline, TileOrder::Spiral, TileOrder::Hilbert] {
+            for (width, height, size) in [(64, 64, 16), (37, 23, 8), (5, 100, 16), (17, 3, 1)] {
+                let mut covered = vec![0; width * height];
+                for tile in tiles(width, height, size, order) {
+                    assert!(tile.columns.len() <= size && tile.rows.len() <= size);
+                    tile.pixels().for_each(|(x, y)| covered[y * width + x] += 1);
+                }
+                assert!(
+                    covered.iter().all(|&n| n == 1),
+                    "{:?} {}x{} in tiles of {}",
+                    order,
+                    width,
+                    height,
+                    size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hilbert_index_is_a_bijection() {
+        for n in [1, 2, 4, 8, 32] {
+            let mut seen = vec![false; n * n];
+            for y in 0..n {
+                for x in 0..n {
+                    let d = hilbert_index(n, x, y);
+                    assert!(d < n * n && !seen[d], "({}, {}) of {}", x, y, n);
+                    seen[d] = true;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hilbert_curve_steps_between_neighbours() {
+        let n = 16;
+        let mut cells = vec![(0, 0); n * n];
+        for y in 0..n {
+            for x in 0..n {
+                cells[hilbert_index(n, x, y)] = (x, y);
+            }
+        }
+        for pair in cells.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            assert_eq!(x0.abs_diff(x1) + y0.abs_diff(y1), 1);
+        }
+    }
+}
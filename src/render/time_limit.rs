@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+/// When a render given a time limit stops. A pass is only started when it is
+/// expected to end in time, from the mean duration of the passes so far, and
+/// a pass still running at the deadline is to be cut short.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use raytracing::render::TimeLimit;
+///
+/// let start = Instant::now();
+/// let mut limit = TimeLimit::new(start, Duration::from_secs(10));
+/// assert!(limit.carry_on(start, false));
+///
+/// limit.pass_done(Duration::from_secs(4));
+/// assert!(limit.carry_on(start + Duration::from_secs(4), false));
+/// limit.pass_done(Duration::from_secs(4));
+/// assert!(!limit.carry_on(start + Duration::from_secs(8), false));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeLimit {
+    deadline: Instant,
+    passes: u32,
+    pass_time: Duration,
+}
+
+impl TimeLimit {
+    /// A limit of the given time from the start of the render.
+    pub fn new(start: Instant, limit: Duration) -> Self {
+        TimeLimit {
+            deadline: start + limit,
+            passes: 0,
+            pass_time: Duration::ZERO,
+        }
+    }
+
+    /// When the render must stop, cutting the current pass short.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Records a pass rendered to its end in the given time.
+    pub fn pass_done(&mut self, duration: Duration) {
+        self.passes += 1;
+        self.pass_time += duration;
+    }
+
+    /// Whether the render carries on at `now`: a pass cut short before the
+    /// deadline is finished, a new one is only started when it is expected
+    /// to end in time.
+    pub fn carry_on(&self, now: Instant, in_pass: bool) -> bool {
+        if now >= self.deadline {
+            return false;
+        }
+        if in_pass || self.passes == 0 {
+            return true;
+        }
+        now + self.pass_time / self.passes <= self.deadline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expects_passes_to_take_their_mean_duration() {
+        let start = Instant::now();
+        let seconds = Duration::from_secs;
+        let mut limit = TimeLimit::new(start, seconds(10));
+
+        // A slow first pass is balanced by quicker ones
+        limit.pass_done(seconds(5));
+        assert!(limit.carry_on(start + seconds(5), false));
+        limit.pass_done(seconds(1));
+        assert!(limit.carry_on(start + seconds(6), false));
+        limit.pass_done(seconds(1));
+        assert!(!limit.carry_on(start + seconds(8), false));
+        assert!(limit.carry_on(start + seconds(7), false));
+    }
+
+    #[test]
+    fn finishes_passes_cut_short_before_the_deadline() {
+        let start = Instant::now();
+        let mut limit = TimeLimit::new(start, Duration::from_secs(10));
+        limit.pass_done(Duration::from_secs(6));
+
+        assert!(limit.carry_on(start + Duration::from_secs(9), true));
+        assert!(!limit.carry_on(start + Duration::from_secs(9), false));
+        assert!(!limit.carry_on(limit.deadline(), true));
+    }
+}
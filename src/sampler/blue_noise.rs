@@ -0,0 +1,170 @@
+use std::sync::OnceLock;
+
+use crate::sampler::sobol::scrambled_sobol;
+use crate::sampler::{hash, SamplePosition, Sampler, ONE_MINUS_EPSILON};
+
+// Side of the tiled blue-noise mask, in pixels
+const MASK_SIZE: usize = 64;
+
+// Ranks of the pixels of a toroidal blue-noise mask, built with Ulichney's
+// void-and-cluster method. Pixels ranked close to each other lie far apart.
+fn void_and_cluster(size: usize, seed: u64) -> Vec<u32> {
+    let n = size * size;
+
+    // Gaussian energy one pixel spreads to the others, by toroidal offset
+    let sigma = 1.5f32;
+    let mut kernel = vec![0.0; n];
+    for dy in 0..size {
+        for dx in 0..size {
+            let x = dx.min(size - dx) as f32;
+            let y = dy.min(size - dy) as f32;
+            kernel[dy * size + dx] = (-(x * x + y * y) / (2.0 * sigma * sigma)).exp();
+        }
+    }
+    let spread = |energy: &mut Vec<f32>, pixel: usize, sign: f32| {
+        let (px, py) = (pixel % size, pixel / size);
+        for (i, e) in energy.iter_mut().enumerate() {
+            let dx = (i % size + size - px) % size;
+            let dy = (i / size + size - py) % size;
+            *e += sign * kernel[dy * size + dx];
+        }
+    };
+    // Most crowded set pixel, or emptiest unset one
+    let extreme = |pattern: &Vec<bool>, energy: &Vec<f32>, set: bool| {
+        let candidates = (0..n).filter(|&i| pattern[i] == set);
+        if set {
+            candidates.max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+        } else {
+            candidates.min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+        }
+        .unwrap()
+    };
+
+    // Random initial pattern with a tenth of the pixels set
+    let mut pattern = vec![false; n];
+    let mut energy = vec![0.0; n];
+    let initial = n / 10;
+    let mut set = 0;
+    for i in 0.. {
+        if set == initial {
+            break;
+        }
+        let pixel = (hash(&[seed, i]) % n as u64) as usize;
+        if !pattern[pixel] {
+            pattern[pixel] = true;
+            spread(&mut energy, pixel, 1.0);
+            set += 1;
+        }
+    }
+
+    // Move pixels from clusters to voids until the pattern is even
+    for _ in 0..n {
+        let cluster = extreme(&pattern, &energy, true);
+        pattern[cluster] = false;
+        spread(&mut energy, cluster, -1.0);
+
+        let void = extreme(&pattern, &energy, false);
+        pattern[void] = true;
+        spread(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; n];
+
+    // Initial pixels are ranked by removing the most crowded ones first
+    let (mut removed, mut removed_energy) = (pattern.clone(), energy.clone());
+    for rank in (0..initial).rev() {
+        let cluster = extreme(&removed, &removed_energy, true);
+        removed[cluster] = false;
+        spread(&mut removed_energy, cluster, -1.0);
+        ranks[cluster] = rank as u32;
+    }
+
+    // The others by filling the emptiest voids first
+    for rank in initial..n {
+        let void = extreme(&pattern, &energy, false);
+        pattern[void] = true;
+        spread(&mut energy, void, 1.0);
+        ranks[void] = rank as u32;
+    }
+
+    ranks
+}
+
+fn mask() -> &'static [f32] {
+    static MASK: OnceLock<Vec<f32>> = OnceLock::new();
+    MASK.get_or_init(|| {
+        let n = (MASK_SIZE * MASK_SIZE) as f32;
+        void_and_cluster(MASK_SIZE, 0)
+            .into_iter()
+            .map(|rank| (rank as f32 + 0.5) / n)
+            .collect()
+    })
+}
+
+// Every pixel draws the same Owen-scrambled Sobol values, shifted around the
+// unit interval by the value of a blue-noise mask at the pixel. The mask is
+// moved around for every dimension.
+pub struct BlueNoiseSampler {
+    seed: u64,
+    position: SamplePosition,
+    mask: &'static [f32],
+}
+
+impl BlueNoiseSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            position: SamplePosition::default(),
+            mask: mask(),
+        }
+    }
+
+    fn shift(&self, dimension: u32) -> f32 {
+        let offset = hash(&[self.seed, dimension as u64, 1]);
+        let x = (self.position.pixel.0 % MASK_SIZE + offset as usize % MASK_SIZE) % MASK_SIZE;
+        let y =
+            (self.position.pixel.1 % MASK_SIZE + (offset >> 32) as usize % MASK_SIZE) % MASK_SIZE;
+        self.mask[y * MASK_SIZE + x]
+    }
+
+    fn rotate(value: f32, shift: f32) -> f32 {
+        let value = value + shift;
+        let value = if value >= 1.0 { value - 1.0 } else { value };
+        value.min(ONE_MINUS_EPSILON)
+    }
+}
+
+impl Sampler for BlueNoiseSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: u32) {
+        self.position.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.position.advance(1);
+        let seed = hash(&[self.seed, dimension as u64]);
+
+        Self::rotate(
+            scrambled_sobol(self.position.index, seed, 0),
+            self.shift(dimension),
+        )
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let dimension = self.position.advance(2);
+        let seed = hash(&[self.seed, dimension as u64]);
+
+        (
+            Self::rotate(
+                scrambled_sobol(self.position.index, seed, 0),
+                self.shift(dimension),
+            ),
+            Self::rotate(
+                scrambled_sobol(self.position.index, seed, 1),
+                self.shift(dimension + 1),
+            ),
+        )
+    }
+}
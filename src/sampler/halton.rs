@@ -0,0 +1,66 @@
+use crate::sampler::{hash, to_unit_float, SamplePosition, Sampler, ONE_MINUS_EPSILON};
+
+// Bases of the first dimensions, later ones take independent values
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+// Digits of index in the given base mirrored around the radix point
+fn radical_inverse(base: u32, mut index: u32) -> f32 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_n = 1.0;
+    let mut reversed = 0u64;
+    while index > 0 {
+        reversed = reversed * base as u64 + (index % base) as u64;
+        inv_base_n *= inv_base;
+        index /= base;
+    }
+    ((reversed as f64 * inv_base_n) as f32).min(ONE_MINUS_EPSILON)
+}
+
+// Dimension d of sample i is the radical inverse of i in the d-th prime base,
+// shifted around the unit interval by a random offset of each pixel and
+// dimension (Cranley-Patterson rotation). Dimensions past the bases would
+// repeat the patterns of the first ones, they are independent instead.
+pub struct HaltonSampler {
+    seed: u64,
+    position: SamplePosition,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            position: SamplePosition::default(),
+        }
+    }
+
+    fn value(&self, dimension: u32) -> f32 {
+        let offset = hash(&[self.position.pixel_hash(self.seed), dimension as u64]);
+        let base = match PRIMES.get(dimension as usize) {
+            Some(&base) => base,
+            None => return to_unit_float(hash(&[offset, self.position.index as u64]) as u32),
+        };
+
+        let value = radical_inverse(base, self.position.index) + to_unit_float(offset as u32);
+        let value = if value >= 1.0 { value - 1.0 } else { value };
+        value.min(ONE_MINUS_EPSILON)
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: u32) {
+        self.position.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.position.advance(1);
+        self.value(dimension)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let dimension = self.position.advance(2);
+        (self.value(dimension), self.value(dimension + 1))
+    }
+}
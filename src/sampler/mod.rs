@@ -0,0 +1,295 @@
+//! Sample values driving the paths, from independent random numbers to
+//! low-discrepancy sequences.
+//!
+//! ```
+//! use raytracing::sampler::SamplerKind;
+//!
+//! let mut sampler = SamplerKind::Sobol.build(16, 0);
+//! sampler.start_pixel_sample((3, 4), 0);
+//! let (x, y) = sampler.get_2d();
+//! assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+//! ```
+
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+use crate::geometry::Vec3;
+use crate::sampler::blue_noise::BlueNoiseSampler;
+use crate::sampler::halton::HaltonSampler;
+use crate::sampler::sobol::SobolSampler;
+use crate::sampler::stratified::StratifiedSampler;
+
+mod blue_noise;
+mod halton;
+mod sobol;
+mod stratified;
+
+/// Source of the sample values driving a path: pixel position, lens, time
+/// and scattering. Every value drawn within a sample of a pixel is a new
+/// dimension of that sample.
+pub trait Sampler {
+    /// Starts the index-th sample of a pixel, back at its first dimension.
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: u32);
+
+    /// Next dimension of the sample, in [0, 1).
+    fn get_1d(&mut self) -> f32;
+    /// Next two dimensions of the sample, in [0, 1).
+    fn get_2d(&mut self) -> (f32, f32);
+}
+
+/// The samplers to choose from.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SamplerKind {
+    /// Independent uniform values.
+    Independent,
+    /// Jittered strata shuffled between dimensions.
+    Stratified,
+    /// Randomized Halton sequence.
+    Halton,
+    /// Owen-scrambled Sobol sequence.
+    Sobol,
+    /// Low-discrepancy values rotated by a blue-noise mask, so that errors
+    /// between neighbouring pixels look like blue noise.
+    BlueNoise,
+}
+
+impl SamplerKind {
+    /// Names of the samplers on the command line and in scene files.
+    pub const NAMES: &'static [&'static str] =
+        &["independent", "stratified", "halton", "sobol", "blue-noise"];
+
+    /// Sampler of the given number of samples per pixel, the values of every
+    /// pixel sample only depend on the seed.
+    pub fn build(self, samples_per_pixel: u32, seed: u64) -> Box<dyn Sampler> {
+        match self {
+            SamplerKind::Independent => Box::new(IndependentSampler::new(seed)),
+            SamplerKind::Stratified => Box::new(StratifiedSampler::new(samples_per_pixel, seed)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(seed)),
+            SamplerKind::BlueNoise => Box::new(BlueNoiseSampler::new(seed)),
+        }
+    }
+}
+
+impl FromStr for SamplerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "independent" => Ok(SamplerKind::Independent),
+            "stratified" => Ok(SamplerKind::Stratified),
+            "halton" => Ok(SamplerKind::Halton),
+            "sobol" => Ok(SamplerKind::Sobol),
+            "blue-noise" => Ok(SamplerKind::BlueNoise),
+            _ => Err(format!("unknown sampler '{}'", s)),
+        }
+    }
+}
+
+// Finalizer of SplitMix64
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Well mixed hash of a few values, stands in for random numbers which must
+// be the same whatever the order pixels are rendered in
+pub(crate) fn hash(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .fold(0, |h, &v| mix(h.wrapping_add(0x9e37_79b9_7f4a_7c15) ^ v))
+}
+
+// Largest float below 1
+pub(crate) const ONE_MINUS_EPSILON: f32 = 1.0 - f32::EPSILON / 2.0;
+
+// Maps the high bits of a 32-bit value to [0, 1)
+pub(crate) fn to_unit_float(bits: u32) -> f32 {
+    (bits >> 8) as f32 / (1u32 << 24) as f32
+}
+
+// Where a sampler stands in the sample space
+#[derive(Default)]
+struct SamplePosition {
+    pixel: (usize, usize),
+    index: u32,
+    dimension: u32,
+}
+
+impl SamplePosition {
+    fn start(&mut self, pixel: (usize, usize), index: u32) {
+        *self = SamplePosition {
+            pixel,
+            index,
+            dimension: 0,
+        };
+    }
+
+    // Moves on by count dimensions, returning the first one
+    fn advance(&mut self, count: u32) -> u32 {
+        let dimension = self.dimension;
+        self.dimension += count;
+        dimension
+    }
+
+    fn pixel_hash(&self, seed: u64) -> u64 {
+        hash(&[seed, self.pixel.0 as u64, self.pixel.1 as u64])
+    }
+}
+
+pub(crate) struct IndependentSampler {
+    seed: u64,
+    position: SamplePosition,
+}
+
+impl IndependentSampler {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            position: SamplePosition::default(),
+        }
+    }
+
+    fn value(&self, dimension: u32) -> f32 {
+        let bits = hash(&[
+            self.position.pixel_hash(self.seed),
+            self.position.index as u64,
+            dimension as u64,
+        ]);
+        to_unit_float(bits as u32)
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: u32) {
+        self.position.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.position.advance(1);
+        self.value(dimension)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let dimension = self.position.advance(2);
+        (self.value(dimension), self.value(dimension + 1))
+    }
+}
+
+// Uniform point of the unit disk, keeping strata of the unit square apart
+pub(crate) fn unit_disk((u, v): (f32, f32)) -> (f32, f32) {
+    let (x, y) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+    if x == 0.0 && y == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    // Concentric mapping from Shirley and Chiu
+    let (r, theta) = if x.abs() > y.abs() {
+        (x, PI / 4.0 * (y / x))
+    } else {
+        (y, PI / 2.0 - PI / 4.0 * (x / y))
+    };
+    let (sin_theta, cos_theta) = theta.sin_cos();
+    (r * cos_theta, r * sin_theta)
+}
+
+// Uniform direction
+pub(crate) fn unit_sphere((u, v): (f32, f32)) -> Vec3 {
+    let z = 1.0 - 2.0 * u;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let (sin_phi, cos_phi) = (2.0 * PI * v).sin_cos();
+    Vec3::new(r * cos_phi, r * sin_phi, z)
+}
+
+// Uniform point of the unit ball
+pub(crate) fn unit_ball(direction: (f32, f32), radius: f32) -> Vec3 {
+    radius.cbrt() * unit_sphere(direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [SamplerKind; 5] = [
+        SamplerKind::Independent,
+        SamplerKind::Stratified,
+        SamplerKind::Halton,
+        SamplerKind::Sobol,
+        SamplerKind::BlueNoise,
+    ];
+
+    // Values of the first dimensions of a pixel sample
+    fn draw(sampler: &mut dyn Sampler, pixel: (usize, usize), index: u32) -> Vec<f32> {
+        sampler.start_pixel_sample(pixel, index);
+        (0..40)
+            .flat_map(|_| {
+                let (x, y) = sampler.get_2d();
+                [x, y, sampler.get_1d()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn values_lie_in_unit_interval() {
+        for kind in KINDS {
+            let mut sampler = kind.build(16, 7);
+            for index in 0..16 {
+                let values = draw(sampler.as_mut(), (index as usize, usize::MAX), index);
+                assert!(values.iter().all(|v| (0.0..1.0).contains(v)), "{:?}", kind);
+            }
+        }
+    }
+
+    #[test]
+    fn values_only_depend_on_seed_pixel_and_index() {
+        for kind in KINDS {
+            let mut sampler = kind.build(16, 7);
+            let first = draw(sampler.as_mut(), (3, 4), 5);
+            draw(sampler.as_mut(), (8, 1), 2);
+            assert_eq!(draw(sampler.as_mut(), (3, 4), 5), first, "{:?}", kind);
+            assert_eq!(draw(kind.build(16, 7).as_mut(), (3, 4), 5), first);
+            assert_ne!(draw(kind.build(16, 8).as_mut(), (3, 4), 5), first);
+        }
+    }
+
+    #[test]
+    fn halton_dimensions_past_the_bases_do_not_repeat_the_first_ones() {
+        let mut sampler = SamplerKind::Halton.build(64, 0);
+        let dimension = |sampler: &mut dyn Sampler, index, dimension| {
+            sampler.start_pixel_sample((0, 0), index);
+            (0..dimension).for_each(|_| {
+                sampler.get_1d();
+            });
+            sampler.get_1d()
+        };
+
+        // The gap between dimensions 0 and 32 would be the same for every
+        // sample if they shared their base
+        let gaps: Vec<f32> = (0..8)
+            .map(|index| {
+                let gap =
+                    dimension(sampler.as_mut(), index, 32) - dimension(sampler.as_mut(), index, 0);
+                gap.rem_euclid(1.0)
+            })
+            .collect();
+        assert!(gaps.iter().any(|&gap| (gap - gaps[0]).abs() > 1e-3));
+    }
+
+    #[test]
+    fn stratified_and_sobol_take_one_sample_per_stratum() {
+        for kind in [SamplerKind::Stratified, SamplerKind::Sobol] {
+            let mut sampler = kind.build(16, 3);
+            let mut strata_1d = [0; 16];
+            let mut strata_2d = [0; 16];
+            for index in 0..16 {
+                sampler.start_pixel_sample((5, 9), index);
+                let (x, y) = sampler.get_2d();
+                strata_2d[(y * 4.0) as usize * 4 + (x * 4.0) as usize] += 1;
+                strata_1d[(sampler.get_1d() * 16.0) as usize] += 1;
+            }
+            assert_eq!(strata_1d, [1; 16], "{:?}", kind);
+            assert_eq!(strata_2d, [1; 16], "{:?}", kind);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::sampler::{hash, to_unit_float, SamplePosition, Sampler};
+
+// Generator matrix of the second Sobol dimension, the first one is the
+// identity and amounts to reversing bits
+const SOBOL_DIRECTIONS: [u32; 32] = {
+    let mut directions = [0; 32];
+    let mut v = 1u32 << 31;
+    let mut bit = 0;
+    while bit < 32 {
+        directions[bit] = v;
+        v ^= v >> 1;
+        bit += 1;
+    }
+    directions
+};
+
+fn sobol(index: u32, dimension: usize) -> u32 {
+    if dimension == 0 {
+        return index.reverse_bits();
+    }
+
+    (0..32)
+        .filter(|bit| (index >> bit) & 1 == 1)
+        .fold(0, |x, bit| x ^ SOBOL_DIRECTIONS[bit])
+}
+
+// Hash permuting only higher bits from lower ones, from Laine and Karras
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+// Owen scrambling: every digit is flipped according to the digits before it
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+// Owen-scrambled and shuffled point index of the first two Sobol dimensions
+pub(crate) fn scrambled_sobol(index: u32, seed: u64, dimension: usize) -> f32 {
+    let index = nested_uniform_scramble(index, seed as u32);
+    let value = sobol(index, dimension);
+    to_unit_float(nested_uniform_scramble(
+        value,
+        hash(&[seed, dimension as u64]) as u32,
+    ))
+}
+
+// Burley's "Practical Hash-based Owen Scrambling": every 1D or 2D draw takes
+// the first Sobol dimensions, with their own scrambling and their own shuffle
+// of the points so that draws do not correlate. Works best with powers of two
+// samples per pixel.
+pub struct SobolSampler {
+    seed: u64,
+    position: SamplePosition,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            position: SamplePosition::default(),
+        }
+    }
+
+    fn draw_seed(&self, dimension: u32) -> u64 {
+        hash(&[self.position.pixel_hash(self.seed), dimension as u64])
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: u32) {
+        self.position.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.position.advance(1);
+        let seed = self.draw_seed(dimension);
+        scrambled_sobol(self.position.index, seed, 0)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let dimension = self.position.advance(2);
+        let seed = self.draw_seed(dimension);
+        (
+            scrambled_sobol(self.position.index, seed, 0),
+            scrambled_sobol(self.position.index, seed, 1),
+        )
+    }
+}
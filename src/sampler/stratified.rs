@@ -0,0 +1,93 @@
+use crate::sampler::{hash, to_unit_float, SamplePosition, Sampler, ONE_MINUS_EPSILON};
+
+// Element i of a pseudo-random permutation of 0..len picked by seed, from
+// Kensler's "Correlated Multi-Jittered Sampling"
+fn permute(mut i: u32, len: u32, seed: u32) -> u32 {
+    let mut w = len - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < len {
+            break;
+        }
+    }
+
+    (i.wrapping_add(seed)) % len
+}
+
+// Splits every dimension of the samples of a pixel into as many strata as
+// there are samples, 2D draws into a square grid. Each dimension visits its
+// strata in its own order so that dimensions do not correlate.
+pub struct StratifiedSampler {
+    samples_per_pixel: u32,
+    grid_size: u32,
+    seed: u64,
+    position: SamplePosition,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        let samples_per_pixel = samples_per_pixel.max(1);
+        Self {
+            samples_per_pixel,
+            grid_size: (samples_per_pixel as f32).sqrt().ceil() as u32,
+            seed,
+            position: SamplePosition::default(),
+        }
+    }
+
+    // Stratum of the current sample among count ones, and the jitter hash
+    fn stratum(&self, dimension: u32, count: u32) -> (u32, u64) {
+        let dimension_hash = hash(&[self.position.pixel_hash(self.seed), dimension as u64]);
+        let stratum = permute(self.position.index % count, count, dimension_hash as u32);
+        (stratum, hash(&[dimension_hash, self.position.index as u64]))
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: u32) {
+        self.position.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.position.advance(1);
+        let (stratum, jitter) = self.stratum(dimension, self.samples_per_pixel);
+
+        let value = (stratum as f32 + to_unit_float(jitter as u32)) / self.samples_per_pixel as f32;
+        value.min(ONE_MINUS_EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let dimension = self.position.advance(2);
+        let (stratum, jitter) = self.stratum(dimension, self.grid_size * self.grid_size);
+
+        let (x, y) = (stratum % self.grid_size, stratum / self.grid_size);
+        let size = self.grid_size as f32;
+        (
+            ((x as f32 + to_unit_float(jitter as u32)) / size).min(ONE_MINUS_EPSILON),
+            ((y as f32 + to_unit_float((jitter >> 32) as u32)) / size).min(ONE_MINUS_EPSILON),
+        )
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::color::Color;
+use crate::environment::{ConstantEnvironment, GradientEnvironment};
+use crate::film::filter::FilterKind;
+use crate::geometry::box_shape::BoxShape;
+use crate::geometry::constant_medium::ConstantMedium;
+use crate::geometry::hittable::HittableList;
+use crate::geometry::instance::Instance;
+use crate::geometry::matrix::Matrix4;
+use crate::geometry::quad::Quad;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::{Point3, Vec3};
+use crate::material::dielectric::Dielectric;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::material::isotropic::Isotropic;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::material::Material;
+use crate::sampler::SamplerKind;
+use crate::scene::{fingerprint, CameraSettings, ImageSettings, Scene};
+
+fn white() -> Box<dyn Material + Send + Sync> {
+    Box::new(Lambertian::new(&Color::new(0.73, 0.73, 0.73)))
+}
+
+// The five walls of the Cornell box and a ceiling light spanned by u and v,
+// which is returned for sampling
+fn push_cornell_room(
+    world: &mut HittableList<'static>,
+    light_corner: Point3,
+    light_u: Vec3,
+    light_v: Vec3,
+    emit: &Color,
+) -> Arc<Quad> {
+    let red = Color::new(0.65, 0.05, 0.05);
+    let green = Color::new(0.12, 0.45, 0.15);
+    let white = Color::new(0.73, 0.73, 0.73);
+
+    let (x, y, z) = (
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+    );
+    for (q, u, v, albedo) in [
+        (Point3::new(555.0, 0.0, 0.0), y, z, green),
+        (Point3::new(0.0, 0.0, 0.0), y, z, red),
+        (Point3::new(0.0, 0.0, 0.0), x, z, white),
+        (Point3::new(0.0, 555.0, 0.0), x, z, white),
+        (Point3::new(0.0, 0.0, 555.0), x, y, white),
+    ] {
+        world.push(Box::new(Quad::new(
+            q,
+            u,
+            v,
+            Box::new(Lambertian::new(&albedo)),
+        )));
+    }
+
+    let light = Arc::new(Quad::new(
+        light_corner,
+        light_u,
+        light_v,
+        Box::new(DiffuseLight::new(emit)),
+    ));
+    world.push(Box::new(light.clone()));
+    light
+}
+
+impl Scene {
+    /// The final scene of "Ray Tracing in One Weekend".
+    pub fn random(rng: &mut impl Rng) -> Self {
+        let aspect_ratio = 3.0f32 / 2.0f32;
+        let image_width = 1200;
+
+        Scene {
+            image: ImageSettings {
+                width: image_width,
+                height: ((image_width as f32) / aspect_ratio) as usize,
+                samples_per_pixel: 500,
+                min_samples: 16,
+                noise_threshold: Option::None,
+                min_depth: 5,
+                max_depth: Option::None,
+                sampler: SamplerKind::Sobol,
+                filter: FilterKind::Box,
+                filter_radius: FilterKind::Box.default_radius(),
+            },
+            camera: CameraSettings {
+                lookfrom: Point3::new(13.0, 2.0, 3.0),
+                lookat: Point3::new(0.0, 0.0, 0.0),
+                vup: Vec3::new(0.0, 1.0, 0.0),
+                vfov_deg: 20.0,
+                aperture: 0.1,
+                focus_dist: 10.0,
+                shutter_open: 0.0,
+                shutter_close: 0.0,
+            },
+            environment: Box::new(GradientEnvironment::sky()),
+            world: HittableList::random_scene(rng),
+            lights: HittableList::new(),
+            source: fingerprint("random"),
+        }
+    }
+
+    /// The three material showcase spheres of "Ray Tracing in One Weekend".
+    pub fn three_spheres() -> Self {
+        let mut world = HittableList::new();
+        let spheres: [(Point3, f32, Box<dyn Material + Send + Sync>); 5] = [
+            (
+                Point3::new(0.0, -100.5, -1.0),
+                100.0,
+                Box::new(Lambertian::new(&Color::new(0.8, 0.8, 0.0))),
+            ),
+            (
+                Point3::new(0.0, 0.0, -1.0),
+                0.5,
+                Box::new(Lambertian::new(&Color::new(0.1, 0.2, 0.5))),
+            ),
+            (
+                Point3::new(-1.0, 0.0, -1.0),
+                0.5,
+                Box::new(Dielectric::new(1.5)),
+            ),
+            (
+                Point3::new(-1.0, 0.0, -1.0),
+                -0.45,
+                Box::new(Dielectric::new(1.5)),
+            ),
+            (
+                Point3::new(1.0, 0.0, -1.0),
+                0.5,
+                Box::new(Metal::new(&Color::new(0.8, 0.6, 0.2), 0.0)),
+            ),
+        ];
+        for (center, radius, material) in spheres {
+            world.push(Box::new(Sphere::new(center, radius, material)));
+        }
+
+        Scene {
+            image: ImageSettings {
+                width: 400,
+                height: 225,
+                samples_per_pixel: 100,
+                min_samples: 16,
+                noise_threshold: Option::None,
+                min_depth: 5,
+                max_depth: Option::None,
+                sampler: SamplerKind::Sobol,
+                filter: FilterKind::Box,
+                filter_radius: FilterKind::Box.default_radius(),
+            },
+            camera: CameraSettings {
+                lookfrom: Point3::new(3.0, 3.0, 2.0),
+                lookat: Point3::new(0.0, 0.0, -1.0),
+                vup: Vec3::new(0.0, 1.0, 0.0),
+                vfov_deg: 20.0,
+                aperture: 2.0,
+                focus_dist: (Point3::new(3.0, 3.0, 2.0) - Point3::new(0.0, 0.0, -1.0)).length(),
+                shutter_open: 0.0,
+                shutter_close: 0.0,
+            },
+            environment: Box::new(GradientEnvironment::sky()),
+            world,
+            lights: HittableList::new(),
+            source: fingerprint("three_spheres"),
+        }
+    }
+
+    /// Cornell box lit by an area light, with a glass and a metal sphere.
+    pub fn cornell_box() -> Self {
+        let mut world = HittableList::new();
+        let light = push_cornell_room(
+            &mut world,
+            Point3::new(213.0, 554.0, 227.0),
+            Vec3::new(130.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 105.0),
+            &Color::new(15.0, 15.0, 15.0),
+        );
+
+        world.push(Box::new(Sphere::new(
+            Point3::new(190.0, 90.0, 190.0),
+            90.0,
+            Box::new(Dielectric::new(1.5)),
+        )));
+        world.push(Box::new(Sphere::new(
+            Point3::new(380.0, 120.0, 380.0),
+            120.0,
+            Box::new(Metal::new(&Color::new(0.8, 0.85, 0.88), 0.0)),
+        )));
+
+        Self::cornell_scene("cornell_box", world, light)
+    }
+
+    /// Cornell box with two blocks of smoke and fog under a wide light.
+    pub fn cornell_smoke() -> Self {
+        let mut world = HittableList::new();
+        let light = push_cornell_room(
+            &mut world,
+            Point3::new(113.0, 554.0, 127.0),
+            Vec3::new(330.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 305.0),
+            &Color::new(7.0, 7.0, 7.0),
+        );
+
+        for (size, angle_deg, offset, color) in [
+            (
+                Vec3::new(165.0, 330.0, 165.0),
+                15.0,
+                Vec3::new(265.0, 0.0, 295.0),
+                Color::new(0.0, 0.0, 0.0),
+            ),
+            (
+                Vec3::new(165.0, 165.0, 165.0),
+                -18.0,
+                Vec3::new(130.0, 0.0, 65.0),
+                Color::new(1.0, 1.0, 1.0),
+            ),
+        ] {
+            let boundary = BoxShape::new(Point3::new(0.0, 0.0, 0.0), size, white);
+            let medium =
+                ConstantMedium::new(Box::new(boundary), 0.01, Box::new(Isotropic::new(&color)));
+            world.push(Box::new(Instance::new(
+                Arc::new(medium),
+                Matrix4::translation(&offset)
+                    * Matrix4::rotation(&Vec3::new(0.0, 1.0, 0.0), angle_deg),
+            )));
+        }
+
+        Self::cornell_scene("cornell_smoke", world, light)
+    }
+
+    fn cornell_scene(name: &str, world: HittableList<'static>, light: Arc<Quad>) -> Self {
+        let mut lights = HittableList::new();
+        lights.push(Box::new(light));
+
+        Scene {
+            image: ImageSettings {
+                width: 600,
+                height: 600,
+                samples_per_pixel: 200,
+                min_samples: 16,
+                noise_threshold: Option::None,
+                min_depth: 5,
+                max_depth: Option::None,
+                sampler: SamplerKind::Sobol,
+                filter: FilterKind::Box,
+                filter_radius: FilterKind::Box.default_radius(),
+            },
+            camera: CameraSettings {
+                lookfrom: Point3::new(278.0, 278.0, -800.0),
+                lookat: Point3::new(278.0, 278.0, 0.0),
+                vup: Vec3::new(0.0, 1.0, 0.0),
+                vfov_deg: 40.0,
+                aperture: 0.0,
+                focus_dist: 10.0,
+                shutter_open: 0.0,
+                shutter_close: 0.0,
+            },
+            environment: Box::new(ConstantEnvironment::new(&Color::new(0.0, 0.0, 0.0))),
+            world,
+            lights,
+            source: fingerprint(name),
+        }
+    }
+}
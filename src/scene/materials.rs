@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::color::Color;
+use crate::material::dielectric::Dielectric;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::material::isotropic::Isotropic;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::material::Material;
+use crate::scene::parser::{Table, Value};
+use crate::scene::SceneError;
+use crate::texture::image_texture::{ImageTexture, WrapMode};
+use crate::texture::perlin::{NoiseStyle, NoiseTexture, Perlin};
+use crate::texture::{CheckerTexture, SolidColor, Texture};
+
+pub type TextureMap = HashMap<String, Arc<dyn Texture + Send + Sync>>;
+
+fn unknown_value(table: &Table, key: &str, kind: &str, value: &str) -> SceneError {
+    SceneError::invalid(
+        table.get(key).unwrap().line,
+        format!("unknown {} '{}'", kind, value),
+    )
+}
+
+// Either a color or the name of a texture defined earlier
+fn texture_ref(
+    table: &Table,
+    key: &str,
+    textures: &TextureMap,
+) -> Result<Option<Arc<dyn Texture + Send + Sync>>, SceneError> {
+    match table.get(key).map(|entry| &entry.value) {
+        Some(Value::String(name)) => textures
+            .get(name)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| unknown_value(table, key, "texture", name)),
+        _ => Ok(table
+            .vec3(key)?
+            .map(|color| Arc::new(SolidColor::new(&color)) as Arc<dyn Texture + Send + Sync>)),
+    }
+}
+
+fn required_texture_ref(
+    table: &Table,
+    key: &str,
+    textures: &TextureMap,
+) -> Result<Arc<dyn Texture + Send + Sync>, SceneError> {
+    texture_ref(table, key, textures)?.ok_or_else(|| {
+        SceneError::invalid(
+            table.line,
+            format!("missing key '{}' in [{}]", key, table.name),
+        )
+    })
+}
+
+pub fn parse_texture(
+    table: &Table,
+    textures: &TextureMap,
+    directory: &Path,
+) -> Result<Arc<dyn Texture + Send + Sync>, SceneError> {
+    let kind = table.required_string("type")?;
+    match kind {
+        "solid" => {
+            table.check_keys(&["type", "color"])?;
+            Ok(Arc::new(SolidColor::new(&table.required_vec3("color")?)))
+        }
+        "checker" => {
+            table.check_keys(&["type", "odd", "even", "scale"])?;
+            Ok(Arc::new(CheckerTexture::new(
+                required_texture_ref(table, "odd", textures)?,
+                required_texture_ref(table, "even", textures)?,
+                table.positive("scale")?.unwrap_or(1.0),
+            )))
+        }
+        "noise" => {
+            table.check_keys(&["type", "style", "color", "scale", "depth", "seed"])?;
+            let style = match table.string("style")?.unwrap_or("noise") {
+                "noise" => NoiseStyle::Noise,
+                "turbulence" => NoiseStyle::Turbulence,
+                "marble" => NoiseStyle::Marble,
+                style => return Err(unknown_value(table, "style", "noise style", style)),
+            };
+            let mut rng = StdRng::seed_from_u64(table.integer("seed")?.unwrap_or(0) as u64);
+
+            Ok(Arc::new(NoiseTexture::new(
+                Perlin::new(&mut rng),
+                style,
+                &table
+                    .vec3("color")?
+                    .unwrap_or_else(|| Color::new(1.0, 1.0, 1.0)),
+                table.positive("scale")?.unwrap_or(1.0),
+                table.integer("depth")?.unwrap_or(7),
+            )))
+        }
+        "image" => {
+            table.check_keys(&["type", "file", "wrap"])?;
+            let wrap = match table.string("wrap")?.unwrap_or("repeat") {
+                "repeat" => WrapMode::Repeat,
+                "clamp" => WrapMode::Clamp,
+                "mirror" => WrapMode::Mirror,
+                wrap => return Err(unknown_value(table, "wrap", "wrap mode", wrap)),
+            };
+            let file = table.required_string("file")?;
+            let texture = ImageTexture::load(&directory.join(file), wrap)
+                .map_err(|err| SceneError::Import(table.get("file").unwrap().line, err))?;
+            Ok(Arc::new(texture))
+        }
+        _ => Err(unknown_value(table, "type", "texture type", kind)),
+    }
+}
+
+#[derive(Clone)]
+pub enum MaterialDesc {
+    Lambertian(Arc<dyn Texture + Send + Sync>),
+    Metal(Arc<dyn Texture + Send + Sync>, f32),
+    Dielectric(f32),
+    DiffuseLight(Color),
+    Isotropic(Arc<dyn Texture + Send + Sync>),
+}
+
+impl MaterialDesc {
+    pub fn parse(table: &Table, textures: &TextureMap) -> Result<Self, SceneError> {
+        let kind = table.required_string("type")?;
+        match kind {
+            "lambertian" => {
+                table.check_keys(&["type", "albedo"])?;
+                Ok(MaterialDesc::Lambertian(required_texture_ref(
+                    table, "albedo", textures,
+                )?))
+            }
+            "metal" => {
+                table.check_keys(&["type", "albedo", "fuzz"])?;
+                Ok(MaterialDesc::Metal(
+                    required_texture_ref(table, "albedo", textures)?,
+                    table.number("fuzz")?.unwrap_or(0.0),
+                ))
+            }
+            "dielectric" => {
+                table.check_keys(&["type", "ir"])?;
+                Ok(MaterialDesc::Dielectric(table.required_number("ir")?))
+            }
+            "diffuse_light" => {
+                table.check_keys(&["type", "emit"])?;
+                Ok(MaterialDesc::DiffuseLight(table.required_vec3("emit")?))
+            }
+            "isotropic" => {
+                table.check_keys(&["type", "albedo"])?;
+                Ok(MaterialDesc::Isotropic(required_texture_ref(
+                    table, "albedo", textures,
+                )?))
+            }
+            _ => Err(unknown_value(table, "type", "material type", kind)),
+        }
+    }
+
+    pub fn is_emissive(&self) -> bool {
+        matches!(self, MaterialDesc::DiffuseLight(_))
+    }
+
+    pub fn build(&self) -> Box<dyn Material + Send + Sync> {
+        match self {
+            MaterialDesc::Lambertian(albedo) => Box::new(Lambertian::textured(albedo.clone())),
+            MaterialDesc::Metal(albedo, fuzz) => Box::new(Metal::textured(albedo.clone(), *fuzz)),
+            MaterialDesc::Dielectric(ir) => Box::new(Dielectric::new(*ir)),
+            MaterialDesc::DiffuseLight(emit) => Box::new(DiffuseLight::new(emit)),
+            MaterialDesc::Isotropic(albedo) => Box::new(Isotropic::textured(albedo.clone())),
+        }
+    }
+}
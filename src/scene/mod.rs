@@ -0,0 +1,943 @@
+//! Scene descriptions, loaded from TOML files or built in code.
+//!
+//! ```
+//! use std::path::Path;
+//!
+//! use raytracing::Scene;
+//!
+//! let scene = Scene::parse(
+//!     r#"
+//!     [image]
+//!     width = 32
+//!     height = 18
+//!     samples_per_pixel = 4
+//!
+//!     [camera]
+//!     lookfrom = [0, 0, 2]
+//!     lookat = [0, 0, 0]
+//!
+//!     [material.red]
+//!     type = "lambertian"
+//!     albedo = [0.8, 0.1, 0.1]
+//!
+//!     [[object]]
+//!     type = "sphere"
+//!     center = [0, 0, 0]
+//!     radius = 0.5
+//!     material = "red"
+//!     "#,
+//!     Path::new("."),
+//! )
+//! .unwrap();
+//! assert_eq!(scene.image.samples_per_pixel, 4);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::environment::{ConstantEnvironment, Environment, EnvironmentMap, GradientEnvironment};
+use crate::film::filter::FilterKind;
+use crate::geometry::box_shape::BoxShape;
+use crate::geometry::camera::Camera;
+use crate::geometry::capsule::Capsule;
+use crate::geometry::cone::Cone;
+use crate::geometry::constant_medium::ConstantMedium;
+use crate::geometry::csg::{Csg, CsgOperation};
+use crate::geometry::cylinder::Cylinder;
+use crate::geometry::disk::Disk;
+use crate::geometry::hittable::{Hittable, HittableList};
+use crate::geometry::instance::Instance;
+use crate::geometry::matrix::Matrix4;
+use crate::geometry::moving_sphere::MovingSphere;
+use crate::geometry::plane::Plane;
+use crate::geometry::quad::Quad;
+use crate::geometry::rect::AxisAlignedRect;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::torus::Torus;
+use crate::geometry::triangle::Triangle;
+use crate::geometry::{Point3, Vec3};
+use crate::import::obj::load_obj;
+use crate::import::ImportError;
+use crate::sampler::{hash, SamplerKind};
+use crate::scene::materials::{parse_texture, MaterialDesc, TextureMap};
+use crate::scene::parser::{Document, Table, Value};
+
+mod builtin;
+mod materials;
+mod parser;
+
+type SharedHittable = Arc<dyn Hittable + Send + Sync>;
+
+// Hash of the description a scene is built from, to tell scenes apart
+fn fingerprint(description: &str) -> u64 {
+    let mut words: Vec<u64> = description
+        .as_bytes()
+        .chunks(8)
+        .map(|chunk| {
+            let mut bytes = [0; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(bytes)
+        })
+        .collect();
+    words.push(description.len() as u64);
+    hash(&words)
+}
+
+// Keys accepted by every object
+const COMMON_KEYS: &[&str] = &["name", "scale", "rotate", "rotate_axis", "translate"];
+
+/// Why a scene could not be loaded, with the line at fault in the file.
+#[derive(Debug)]
+pub enum SceneError {
+    /// The scene file could not be read.
+    Io(io::Error),
+    /// The scene file is malformed or describes something impossible.
+    Invalid {
+        /// Line of the scene file at fault.
+        line: usize,
+        /// What is wrong with it.
+        message: String,
+    },
+    /// A mesh the object at the given line refers to could not be loaded.
+    Import(usize, ImportError),
+}
+
+impl SceneError {
+    /// An error at the given line of the scene file.
+    pub fn invalid(line: usize, message: String) -> Self {
+        SceneError::Invalid { line, message }
+    }
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "{}", err),
+            SceneError::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+            SceneError::Import(line, err) => write!(f, "line {}: {}", line, err),
+        }
+    }
+}
+
+impl Error for SceneError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SceneError::Io(err) => Some(err),
+            SceneError::Invalid { .. } => Option::None,
+            SceneError::Import(_, err) => Some(err),
+        }
+    }
+}
+
+/// What the rendered image looks like, the `[image]` table of scene files.
+pub struct ImageSettings {
+    /// Width of the image in pixels.
+    pub width: usize,
+    /// Height of the image in pixels.
+    pub height: usize,
+    /// Samples of every pixel, the most a pixel can take with adaptive
+    /// sampling.
+    pub samples_per_pixel: u32,
+    /// Samples every pixel takes before adaptive sampling may stop.
+    pub min_samples: u32,
+    /// Adaptive sampling stops sampling a pixel once the standard error of
+    /// its luminance falls under this fraction of it. Every pixel takes all
+    /// its samples without it.
+    pub noise_threshold: Option<f32>,
+    /// Bounces every path takes before Russian roulette may end it.
+    pub min_depth: u32,
+    /// Rays a path is made of at most, as a safety cap. Paths are only ended
+    /// by Russian roulette without it.
+    pub max_depth: Option<u32>,
+    /// How the samples of every pixel are spread.
+    pub sampler: SamplerKind,
+    /// Reconstruction filter weighing the samples.
+    pub filter: FilterKind,
+    /// Radius of the filter in pixels.
+    pub filter_radius: f32,
+}
+
+impl ImageSettings {
+    /// Width over height of the image.
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+}
+
+/// The `[camera]` table of scene files. The [`Camera`] is only built by
+/// [`Scene::camera`] once the final image size is known.
+pub struct CameraSettings {
+    /// Position of the camera.
+    pub lookfrom: Point3,
+    /// Point the camera looks at.
+    pub lookat: Point3,
+    /// Direction that is up in the image.
+    pub vup: Vec3,
+    /// Vertical field of view in degrees.
+    pub vfov_deg: f32,
+    /// Diameter of the lens, everything is in focus when it is zero.
+    pub aperture: f32,
+    /// Distance to the plane in focus.
+    pub focus_dist: f32,
+    /// Time the shutter opens at.
+    pub shutter_open: f32,
+    /// Time the shutter closes at.
+    pub shutter_close: f32,
+}
+
+/// Everything needed to render an image.
+pub struct Scene {
+    /// What the rendered image looks like.
+    pub image: ImageSettings,
+    /// Where the image is seen from.
+    pub camera: CameraSettings,
+    // Radiance of the rays leaving the scene
+    pub(crate) environment: Box<dyn Environment + Send + Sync>,
+    pub(crate) world: HittableList<'static>,
+    // Emitters also in the world that are sampled explicitly
+    pub(crate) lights: HittableList<'static>,
+    // Fingerprint of the scene file or built-in scene, the files it refers
+    // to are not part of it
+    pub(crate) source: u64,
+}
+
+impl Scene {
+    /// The camera described by the camera settings, seeing an image of the
+    /// aspect ratio of the image settings.
+    pub fn camera(&self) -> Camera {
+        Camera::new(
+            self.camera.lookfrom,
+            self.camera.lookat,
+            self.camera.vup,
+            self.camera.vfov_deg,
+            self.image.aspect_ratio(),
+            self.camera.aperture,
+            self.camera.focus_dist,
+        )
+        .with_shutter(self.camera.shutter_open, self.camera.shutter_close)
+    }
+
+    /// Loads a scene file, the files it refers to are relative to it.
+    pub fn load(path: &Path) -> Result<Self, SceneError> {
+        let content = fs::read_to_string(path).map_err(SceneError::Io)?;
+        Self::parse(&content, path.parent().unwrap_or_else(|| Path::new("")))
+    }
+
+    /// Parses a scene description, relative file names are resolved against
+    /// the given directory.
+    pub fn parse(content: &str, directory: &Path) -> Result<Self, SceneError> {
+        let document = parser::parse(content)?;
+
+        for table in &document.tables {
+            let known = match table.name.as_str() {
+                "" => table.is_empty(),
+                "image" | "camera" | "environment" | "object" => true,
+                name => name.starts_with("material.") || name.starts_with("texture."),
+            };
+            if !known {
+                let line = table.entries.first().map_or(table.line, |e| e.line);
+                return Err(SceneError::invalid(
+                    line,
+                    format!("unexpected table [{}]", table.name),
+                ));
+            }
+        }
+
+        let image = Self::parse_image(&document)?;
+        let camera = Self::parse_camera(&document)?;
+        let environment = Self::parse_environment(&document, directory)?;
+
+        // Textures can only refer to the ones defined before them
+        let mut textures = TextureMap::new();
+        for table in &document.tables {
+            if let Some(name) = table.name.strip_prefix("texture.") {
+                let texture = parse_texture(table, &textures, directory)?;
+                textures.insert(name.to_string(), texture);
+            }
+        }
+
+        let mut materials = HashMap::new();
+        for table in &document.tables {
+            if let Some(name) = table.name.strip_prefix("material.") {
+                materials.insert(name.to_string(), MaterialDesc::parse(table, &textures)?);
+            }
+        }
+
+        // Named objects can be combined by later csg objects, which replace
+        // them in the world
+        let mut objects = vec![];
+        let mut meshes = HashMap::new();
+        let mut named = HashMap::new();
+        let mut used = HashSet::new();
+        for table in document.tables_named("object") {
+            let transform = Self::parse_transform(table)?;
+            let shapes: Vec<SharedHittable> =
+                Self::parse_object(table, &materials, directory, &mut meshes, &named)?
+                    .into_iter()
+                    .map(|shape| match transform {
+                        Some(transform) => Arc::new(Instance::new(shape, transform)),
+                        None => shape,
+                    })
+                    .collect();
+
+            if table.string("type")? == Some("csg") {
+                used.insert(table.required_string("left")?);
+                used.insert(table.required_string("right")?);
+            }
+
+            let name = table.string("name")?;
+            if let Some(name) = name {
+                if named.contains_key(name) {
+                    return Err(SceneError::invalid(
+                        table.get("name").unwrap().line,
+                        format!("duplicate object name '{}'", name),
+                    ));
+                }
+                let shape: SharedHittable = match &shapes[..] {
+                    [shape] => shape.clone(),
+                    _ => {
+                        let mut list = HittableList::new();
+                        shapes
+                            .iter()
+                            .for_each(|shape| list.push(Box::new(shape.clone())));
+                        Arc::new(list)
+                    }
+                };
+                named.insert(name.to_string(), shape);
+            }
+            let is_light = transform.is_none() && Self::is_light(table, &materials)?;
+            objects.push((name, shapes, is_light));
+        }
+
+        let mut world = HittableList::new();
+        let mut lights = HittableList::new();
+        for (name, shapes, is_light) in objects {
+            if name.is_none_or(|name| !used.contains(name)) {
+                for shape in shapes {
+                    if is_light {
+                        lights.push(Box::new(shape.clone()));
+                    }
+                    world.push(Box::new(shape));
+                }
+            }
+        }
+
+        Ok(Scene {
+            image,
+            camera,
+            environment,
+            world,
+            lights,
+            source: fingerprint(content),
+        })
+    }
+
+    // Emissive spheres and quads are sampled explicitly, unless transformed
+    // or filled with a medium
+    fn is_light(
+        table: &Table,
+        materials: &HashMap<String, MaterialDesc>,
+    ) -> Result<bool, SceneError> {
+        let kind = table.required_string("type")?;
+        if !["sphere", "quad"].contains(&kind) || table.get("density").is_some() {
+            return Ok(false);
+        }
+        Ok(table
+            .string("material")?
+            .and_then(|name| materials.get(name))
+            .is_some_and(MaterialDesc::is_emissive))
+    }
+
+    fn parse_environment(
+        document: &Document,
+        directory: &Path,
+    ) -> Result<Box<dyn Environment + Send + Sync>, SceneError> {
+        let table = match document.table("environment") {
+            Some(table) => table,
+            None => return Ok(Box::new(GradientEnvironment::sky())),
+        };
+
+        let kind = table.required_string("type")?;
+        match kind {
+            "constant" => {
+                table.check_keys(&["type", "color"])?;
+                Ok(Box::new(ConstantEnvironment::new(
+                    &table.required_vec3("color")?,
+                )))
+            }
+            "gradient" => {
+                table.check_keys(&["type", "bottom", "top"])?;
+                let sky = GradientEnvironment::sky();
+                Ok(Box::new(GradientEnvironment::new(
+                    &table.vec3("bottom")?.unwrap_or(sky.bottom),
+                    &table.vec3("top")?.unwrap_or(sky.top),
+                )))
+            }
+            "map" => {
+                table.check_keys(&["type", "file", "rotation", "intensity"])?;
+                let file = table.required_string("file")?;
+                let environment_map = EnvironmentMap::load(
+                    &directory.join(file),
+                    table.number("rotation")?.unwrap_or(0.0),
+                    table.number("intensity")?.unwrap_or(1.0),
+                )
+                .map_err(|err| SceneError::Import(table.get("file").unwrap().line, err))?;
+                Ok(Box::new(environment_map))
+            }
+            _ => Err(SceneError::invalid(
+                table.get("type").unwrap().line,
+                format!("unknown environment type '{}'", kind),
+            )),
+        }
+    }
+
+    fn parse_image(document: &Document) -> Result<ImageSettings, SceneError> {
+        let default = Table::new("image", 1);
+        let table = document.table("image").unwrap_or(&default);
+        table.check_keys(&[
+            "width",
+            "height",
+            "aspect_ratio",
+            "samples_per_pixel",
+            "min_samples",
+            "noise_threshold",
+            "min_depth",
+            "max_depth",
+            "sampler",
+            "filter",
+            "filter_radius",
+        ])?;
+
+        let width = table.integer("width")?.unwrap_or(400) as usize;
+        let height = match (table.integer("height")?, table.positive("aspect_ratio")?) {
+            (Some(_), Some(_)) => {
+                return Err(SceneError::invalid(
+                    table.get("aspect_ratio").unwrap().line,
+                    "'height' and 'aspect_ratio' are mutually exclusive".to_string(),
+                ))
+            }
+            (Some(height), None) => height as usize,
+            (None, aspect_ratio) => ((width as f32) / aspect_ratio.unwrap_or(16.0 / 9.0)) as usize,
+        };
+        if width < 2 || height < 2 {
+            return Err(SceneError::invalid(
+                table.line,
+                format!("image size {}x{} is too small", width, height),
+            ));
+        }
+
+        let min_depth = table.integer("min_depth")?.unwrap_or(5);
+
+        let max_depth = table.integer("max_depth")?;
+        if max_depth == Some(0) {
+            return Err(SceneError::invalid(
+                table.get("max_depth").unwrap().line,
+                "'max_depth' must be positive".to_string(),
+            ));
+        }
+
+        let sampler = match table.string("sampler")? {
+            Some(name) => name
+                .parse()
+                .map_err(|err| SceneError::invalid(table.get("sampler").unwrap().line, err))?,
+            None => SamplerKind::Sobol,
+        };
+        let filter = match table.string("filter")? {
+            Some(name) => name
+                .parse()
+                .map_err(|err| SceneError::invalid(table.get("filter").unwrap().line, err))?,
+            None => FilterKind::Box,
+        };
+        let filter_radius = table
+            .positive("filter_radius")?
+            .unwrap_or_else(|| filter.default_radius());
+
+        let samples_per_pixel = table.integer("samples_per_pixel")?.unwrap_or(100);
+        if samples_per_pixel == 0 {
+            return Err(SceneError::invalid(
+                table.get("samples_per_pixel").unwrap().line,
+                "'samples_per_pixel' must be positive".to_string(),
+            ));
+        }
+
+        Ok(ImageSettings {
+            width,
+            height,
+            samples_per_pixel,
+            min_samples: table.integer("min_samples")?.unwrap_or(16),
+            noise_threshold: table.positive("noise_threshold")?,
+            min_depth,
+            max_depth,
+            sampler,
+            filter,
+            filter_radius,
+        })
+    }
+
+    fn parse_camera(document: &Document) -> Result<CameraSettings, SceneError> {
+        let table = document
+            .table("camera")
+            .ok_or_else(|| SceneError::invalid(1, "missing [camera] table".to_string()))?;
+        table.check_keys(&[
+            "lookfrom",
+            "lookat",
+            "vup",
+            "vfov",
+            "aperture",
+            "focus_dist",
+            "shutter_open",
+            "shutter_close",
+        ])?;
+
+        let lookfrom = table.required_vec3("lookfrom")?;
+        let lookat = table.required_vec3("lookat")?;
+        if (lookfrom - lookat).near_zero() {
+            return Err(SceneError::invalid(
+                table.get("lookat").unwrap().line,
+                "'lookfrom' and 'lookat' must differ".to_string(),
+            ));
+        }
+
+        // The camera cannot tell its right from its left when looking along
+        // its up direction
+        let vup = table
+            .vec3("vup")?
+            .unwrap_or_else(|| Vec3::new(0.0, 1.0, 0.0));
+        let view = Vec3::unit_vector(&(lookfrom - lookat));
+        if Vec3::cross(&vup, &view).length() < 1e-4 {
+            let line = table
+                .get("vup")
+                .or_else(|| table.get("lookat"))
+                .unwrap()
+                .line;
+            return Err(SceneError::invalid(
+                line,
+                "'vup' must not be parallel to the view direction".to_string(),
+            ));
+        }
+
+        let shutter_open = table.number("shutter_open")?.unwrap_or(0.0);
+        let shutter_close = table.number("shutter_close")?.unwrap_or(shutter_open);
+        if shutter_close < shutter_open {
+            return Err(SceneError::invalid(
+                table.get("shutter_close").unwrap().line,
+                "the shutter must close after it opens".to_string(),
+            ));
+        }
+
+        Ok(CameraSettings {
+            lookfrom,
+            lookat,
+            vup,
+            vfov_deg: table.positive("vfov")?.unwrap_or(40.0),
+            aperture: table.non_negative("aperture")?.unwrap_or(0.0),
+            focus_dist: table
+                .positive("focus_dist")?
+                .unwrap_or_else(|| (lookfrom - lookat).length()),
+            shutter_open,
+            shutter_close,
+        })
+    }
+
+    // Scale, then rotation about the axis, then translation
+    fn parse_transform(table: &Table) -> Result<Option<Matrix4>, SceneError> {
+        let scale = match table.get("scale").map(|entry| &entry.value) {
+            Some(Value::Number(factor)) => {
+                let factor = *factor as f32;
+                Some(Vec3::new(factor, factor, factor))
+            }
+            _ => table.vec3("scale")?,
+        };
+        let rotate = table.number("rotate")?;
+        let translate = table.vec3("translate")?;
+        if rotate.is_none() {
+            if let Some(entry) = table.get("rotate_axis") {
+                return Err(SceneError::invalid(
+                    entry.line,
+                    "'rotate_axis' requires 'rotate'".to_string(),
+                ));
+            }
+        }
+        if scale.is_none() && rotate.is_none() && translate.is_none() {
+            return Ok(Option::None);
+        }
+
+        let axis = table
+            .vec3("rotate_axis")?
+            .unwrap_or_else(|| Vec3::new(0.0, 1.0, 0.0));
+        if axis.near_zero() {
+            return Err(SceneError::invalid(
+                table.get("rotate_axis").unwrap().line,
+                "'rotate_axis' must not be zero".to_string(),
+            ));
+        }
+
+        let transform = Matrix4::translation(&translate.unwrap_or_default())
+            * Matrix4::rotation(&axis, rotate.unwrap_or(0.0))
+            * Matrix4::scaling(&scale.unwrap_or_else(|| Vec3::new(1.0, 1.0, 1.0)));
+        if transform.inverse().is_none() {
+            return Err(SceneError::invalid(
+                table.get("scale").unwrap().line,
+                "'scale' must not be zero".to_string(),
+            ));
+        }
+        Ok(Option::Some(transform))
+    }
+
+    // Non-zero vector
+    fn direction(table: &Table, key: &str) -> Result<Vec3, SceneError> {
+        let direction = table.required_vec3(key)?;
+        if direction.near_zero() {
+            return Err(SceneError::invalid(
+                table.get(key).unwrap().line,
+                format!("'{}' must not be zero", key),
+            ));
+        }
+        Ok(direction)
+    }
+
+    // Distinct centers of the two ends of a shape of revolution
+    fn axis(table: &Table) -> Result<(Point3, Point3), SceneError> {
+        let base = table.required_vec3("base")?;
+        let top = table.required_vec3("top")?;
+        if (top - base).near_zero() {
+            return Err(SceneError::invalid(
+                table.get("top").unwrap().line,
+                "'base' and 'top' must differ".to_string(),
+            ));
+        }
+        Ok((base, top))
+    }
+
+    // Meshes are loaded once per file and shared by every object using them
+    fn parse_object(
+        table: &Table,
+        materials: &HashMap<String, MaterialDesc>,
+        directory: &Path,
+        meshes: &mut HashMap<PathBuf, Vec<SharedHittable>>,
+        named: &HashMap<String, SharedHittable>,
+    ) -> Result<Vec<SharedHittable>, SceneError> {
+        let check_keys = |keys: &[&str]| table.check_keys(&[keys, COMMON_KEYS].concat());
+        let material_desc = || {
+            let name = table.required_string("material")?;
+            materials.get(name).ok_or_else(|| {
+                SceneError::invalid(
+                    table.get("material").unwrap().line,
+                    format!("unknown material '{}'", name),
+                )
+            })
+        };
+        let material = || material_desc().map(MaterialDesc::build);
+
+        // Closed shapes are filled with a volume instead when given a density
+        let solid = |shape: Box<dyn Hittable + Send + Sync>| -> Result<SharedHittable, SceneError> {
+            Ok(match table.positive("density")? {
+                Some(density) => Arc::new(ConstantMedium::new(shape, density, material()?)),
+                None => Arc::from(shape),
+            })
+        };
+
+        let kind = table.required_string("type")?;
+        let shapes = match kind {
+            "sphere" => {
+                check_keys(&["type", "center", "radius", "material", "density"])?;
+                vec![solid(Box::new(Sphere::new(
+                    table.required_vec3("center")?,
+                    table.required_number("radius")?,
+                    material()?,
+                )))?]
+            }
+            "moving_sphere" => {
+                check_keys(&["type", "centers", "times", "radius", "material", "density"])?;
+                let centers = table.vec3_list("centers")?.unwrap_or_default();
+                if centers.is_empty() {
+                    return Err(SceneError::invalid(
+                        table.line,
+                        "missing key 'centers' in [object]".to_string(),
+                    ));
+                }
+
+                // Keyframes are spread evenly over [0, 1] by default
+                let last = (centers.len() - 1).max(1) as f32;
+                let times = table
+                    .numbers("times")?
+                    .unwrap_or_else(|| (0..centers.len()).map(|i| i as f32 / last).collect());
+                if times.len() != centers.len() {
+                    return Err(SceneError::invalid(
+                        table.get("times").unwrap().line,
+                        "'times' and 'centers' must have the same length".to_string(),
+                    ));
+                }
+
+                let radius = table.required_number("radius")?;
+                let sphere = match (&centers[..], &times[..]) {
+                    ([center0, center1], [time0, time1]) => MovingSphere::linear(
+                        *center0,
+                        *time0,
+                        *center1,
+                        *time1,
+                        radius,
+                        material()?,
+                    ),
+                    _ => MovingSphere::keyframed(
+                        times.into_iter().zip(centers).collect(),
+                        radius,
+                        material()?,
+                    ),
+                };
+                vec![solid(Box::new(sphere))?]
+            }
+            "plane" => {
+                check_keys(&["type", "point", "normal", "material"])?;
+                vec![Arc::new(Plane::new(
+                    table.required_vec3("point")?,
+                    Self::direction(table, "normal")?,
+                    material()?,
+                )) as SharedHittable]
+            }
+            "xy_rect" => {
+                check_keys(&["type", "x", "y", "z", "material"])?;
+                vec![Arc::new(AxisAlignedRect::xy(
+                    table.required_interval("x")?,
+                    table.required_interval("y")?,
+                    table.required_number("z")?,
+                    material()?,
+                )) as SharedHittable]
+            }
+            "xz_rect" => {
+                check_keys(&["type", "x", "z", "y", "material"])?;
+                vec![Arc::new(AxisAlignedRect::xz(
+                    table.required_interval("x")?,
+                    table.required_interval("z")?,
+                    table.required_number("y")?,
+                    material()?,
+                )) as SharedHittable]
+            }
+            "yz_rect" => {
+                check_keys(&["type", "y", "z", "x", "material"])?;
+                vec![Arc::new(AxisAlignedRect::yz(
+                    table.required_interval("y")?,
+                    table.required_interval("z")?,
+                    table.required_number("x")?,
+                    material()?,
+                )) as SharedHittable]
+            }
+            "quad" => {
+                check_keys(&["type", "corner", "u", "v", "material"])?;
+                let (u, v) = (table.required_vec3("u")?, table.required_vec3("v")?);
+                if Vec3::cross(&u, &v).near_zero() {
+                    return Err(SceneError::invalid(
+                        table.get("v").unwrap().line,
+                        "'u' and 'v' must not be parallel".to_string(),
+                    ));
+                }
+                vec![
+                    Arc::new(Quad::new(table.required_vec3("corner")?, u, v, material()?))
+                        as SharedHittable,
+                ]
+            }
+            "disk" => {
+                check_keys(&["type", "center", "normal", "radius", "material"])?;
+                vec![Arc::new(Disk::new(
+                    table.required_vec3("center")?,
+                    Self::direction(table, "normal")?,
+                    table.required_number("radius")?,
+                    material()?,
+                )) as SharedHittable]
+            }
+            "box" => {
+                check_keys(&["type", "min", "max", "material", "density"])?;
+                let desc = material_desc()?;
+                vec![solid(Box::new(BoxShape::new(
+                    table.required_vec3("min")?,
+                    table.required_vec3("max")?,
+                    || desc.build(),
+                )))?]
+            }
+            "cylinder" => {
+                check_keys(&["type", "base", "top", "radius", "material", "density"])?;
+                let (base, top) = Self::axis(table)?;
+                vec![solid(Box::new(Cylinder::new(
+                    base,
+                    top,
+                    table.required_number("radius")?,
+                    material()?,
+                )))?]
+            }
+            "cone" => {
+                check_keys(&[
+                    "type",
+                    "base",
+                    "top",
+                    "base_radius",
+                    "top_radius",
+                    "material",
+                    "density",
+                ])?;
+                let (base, top) = Self::axis(table)?;
+                vec![solid(Box::new(Cone::new(
+                    base,
+                    top,
+                    table.required_number("base_radius")?,
+                    table.number("top_radius")?.unwrap_or(0.0),
+                    material()?,
+                )))?]
+            }
+            "capsule" => {
+                check_keys(&["type", "base", "top", "radius", "material", "density"])?;
+                let (base, top) = Self::axis(table)?;
+                vec![solid(Box::new(Capsule::new(
+                    base,
+                    top,
+                    table.required_number("radius")?,
+                    material()?,
+                )))?]
+            }
+            "torus" => {
+                check_keys(&[
+                    "type",
+                    "center",
+                    "axis",
+                    "major_radius",
+                    "minor_radius",
+                    "material",
+                    "density",
+                ])?;
+                let axis = match table.get("axis") {
+                    Some(_) => Self::direction(table, "axis")?,
+                    None => Vec3::new(0.0, 1.0, 0.0),
+                };
+                vec![solid(Box::new(Torus::new(
+                    table.required_vec3("center")?,
+                    axis,
+                    table.required_number("major_radius")?,
+                    table.required_number("minor_radius")?,
+                    material()?,
+                )))?]
+            }
+            "csg" => {
+                check_keys(&["type", "operation", "left", "right"])?;
+                let operation = match table.required_string("operation")? {
+                    "union" => CsgOperation::Union,
+                    "intersection" => CsgOperation::Intersection,
+                    "difference" => CsgOperation::Difference,
+                    operation => {
+                        return Err(SceneError::invalid(
+                            table.get("operation").unwrap().line,
+                            format!("unknown csg operation '{}'", operation),
+                        ))
+                    }
+                };
+                let operand = |key| {
+                    let name = table.required_string(key)?;
+                    named.get(name).cloned().ok_or_else(|| {
+                        SceneError::invalid(
+                            table.get(key).unwrap().line,
+                            format!("unknown object '{}'", name),
+                        )
+                    })
+                };
+                vec![Arc::new(Csg::new(
+                    operation,
+                    Box::new(operand("left")?),
+                    Box::new(operand("right")?),
+                )) as SharedHittable]
+            }
+            "triangle" => {
+                check_keys(&["type", "vertices", "material"])?;
+                let entry = table.get("vertices");
+                match table.vec3_list("vertices")?.as_deref() {
+                    Some([v0, v1, v2]) => {
+                        vec![Arc::new(Triangle::new(*v0, *v1, *v2, material()?)) as SharedHittable]
+                    }
+                    Some(_) => {
+                        return Err(SceneError::invalid(
+                            entry.unwrap().line,
+                            "a triangle needs exactly 3 vertices".to_string(),
+                        ))
+                    }
+                    None => {
+                        return Err(SceneError::invalid(
+                            table.line,
+                            "missing key 'vertices' in [object]".to_string(),
+                        ))
+                    }
+                }
+            }
+            "mesh" => {
+                check_keys(&["type", "file"])?;
+                let path = directory.join(table.required_string("file")?);
+                match meshes.get(&path) {
+                    Some(shapes) => shapes.clone(),
+                    None => {
+                        let shapes: Vec<SharedHittable> = load_obj(&path)
+                            .map_err(|err| {
+                                SceneError::Import(table.get("file").unwrap().line, err)
+                            })?
+                            .into_iter()
+                            .map(|mesh| Arc::new(mesh) as SharedHittable)
+                            .collect();
+                        meshes.insert(path, shapes.clone());
+                        shapes
+                    }
+                }
+            }
+            _ => {
+                return Err(SceneError::invalid(
+                    table.get("type").unwrap().line,
+                    format!("unknown object type '{}'", kind),
+                ))
+            }
+        };
+
+        Ok(shapes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Line of the error parsing the scene
+    fn error_line(content: &str) -> usize {
+        match Scene::parse(content, Path::new(".")) {
+            Err(SceneError::Invalid { line, .. }) => line,
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("the scene is valid"),
+        }
+    }
+
+    #[test]
+    fn rejects_camera_looking_along_vup() {
+        let scene = "[camera]\nlookfrom = [0, 10, 0]\nlookat = [0, 0, 0]\n";
+        assert_eq!(error_line(scene), 3);
+
+        let scene = "[camera]\nlookfrom = [0, 0, 1]\nlookat = [0, 0, 0]\nvup = [0, 0, 2]\n";
+        assert_eq!(error_line(scene), 4);
+    }
+
+    #[test]
+    fn rejects_zero_samples_per_pixel() {
+        let scene = "[image]\nsamples_per_pixel = 0\n\n[camera]\nlookfrom = [0, 0, 1]\nlookat = [0, 0, 0]\n";
+        assert_eq!(error_line(scene), 2);
+    }
+
+    #[test]
+    fn rejects_negative_aperture() {
+        let scene = "[camera]\nlookfrom = [0, 0, 1]\nlookat = [0, 0, 0]\naperture = -0.1\n";
+        assert_eq!(error_line(scene), 4);
+    }
+
+    #[test]
+    fn keeps_max_depth_as_a_cap() {
+        let scene =
+            "[image]\nmax_depth = 50\n\n[camera]\nlookfrom = [0, 0, 1]\nlookat = [0, 0, 0]\n";
+        let scene = Scene::parse(scene, Path::new(".")).unwrap();
+        assert_eq!(scene.image.max_depth, Some(50));
+    }
+}
@@ -0,0 +1,398 @@
+use crate::geometry::Vec3;
+use crate::scene::SceneError;
+
+// Parser for the subset of TOML used by scene files: [tables], [[arrays of
+// tables]] and single line key = value pairs holding numbers, booleans,
+// strings or arrays.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::Bool(_) => "a boolean",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+        }
+    }
+}
+
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    pub line: usize,
+}
+
+pub struct Table {
+    pub name: String,
+    pub line: usize,
+    pub entries: Vec<Entry>,
+}
+
+impl Table {
+    pub fn new(name: &str, line: usize) -> Self {
+        Table {
+            name: name.to_string(),
+            line,
+            entries: vec![],
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.key == key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Typos in key names must not be silently ignored
+    pub fn check_keys(&self, allowed: &[&str]) -> Result<(), SceneError> {
+        match self
+            .entries
+            .iter()
+            .find(|entry| !allowed.contains(&entry.key.as_str()))
+        {
+            Some(entry) => Err(SceneError::invalid(
+                entry.line,
+                format!("unknown key '{}' in [{}]", entry.key, self.name),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn required<T>(&self, key: &str, value: Option<T>) -> Result<T, SceneError> {
+        value.ok_or_else(|| {
+            SceneError::invalid(
+                self.line,
+                format!("missing key '{}' in [{}]", key, self.name),
+            )
+        })
+    }
+
+    fn mismatch(entry: &Entry, expected: &str) -> SceneError {
+        SceneError::invalid(
+            entry.line,
+            format!(
+                "'{}' must be {}, found {}",
+                entry.key,
+                expected,
+                entry.value.type_name()
+            ),
+        )
+    }
+
+    pub fn number(&self, key: &str) -> Result<Option<f32>, SceneError> {
+        self.get(key)
+            .map(|entry| match entry.value {
+                Value::Number(n) => Ok(n as f32),
+                _ => Err(Self::mismatch(entry, "a number")),
+            })
+            .transpose()
+    }
+
+    pub fn required_number(&self, key: &str) -> Result<f32, SceneError> {
+        self.required(key, self.number(key)?)
+    }
+
+    // Strictly positive number
+    pub fn positive(&self, key: &str) -> Result<Option<f32>, SceneError> {
+        match (self.number(key)?, self.get(key)) {
+            (Some(n), Some(entry)) if n <= 0.0 => Err(SceneError::invalid(
+                entry.line,
+                format!("'{}' must be positive", key),
+            )),
+            (n, _) => Ok(n),
+        }
+    }
+
+    pub fn non_negative(&self, key: &str) -> Result<Option<f32>, SceneError> {
+        match (self.number(key)?, self.get(key)) {
+            (Some(n), Some(entry)) if n < 0.0 => Err(SceneError::invalid(
+                entry.line,
+                format!("'{}' must not be negative", key),
+            )),
+            (n, _) => Ok(n),
+        }
+    }
+
+    pub fn integer(&self, key: &str) -> Result<Option<u32>, SceneError> {
+        self.get(key)
+            .map(|entry| match entry.value {
+                Value::Number(n) if n >= 0.0 && n.fract() == 0.0 && n <= u32::MAX as f64 => {
+                    Ok(n as u32)
+                }
+                _ => Err(Self::mismatch(entry, "a positive integer")),
+            })
+            .transpose()
+    }
+
+    pub fn string(&self, key: &str) -> Result<Option<&str>, SceneError> {
+        self.get(key)
+            .map(|entry| match &entry.value {
+                Value::String(s) => Ok(s.as_str()),
+                _ => Err(Self::mismatch(entry, "a string")),
+            })
+            .transpose()
+    }
+
+    pub fn required_string(&self, key: &str) -> Result<&str, SceneError> {
+        self.required(key, self.string(key)?)
+    }
+
+    pub fn numbers(&self, key: &str) -> Result<Option<Vec<f32>>, SceneError> {
+        self.get(key)
+            .map(|entry| match &entry.value {
+                Value::Array(values) => values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Number(n) => Ok(*n as f32),
+                        _ => Err(Self::mismatch(entry, "an array of numbers")),
+                    })
+                    .collect(),
+                _ => Err(Self::mismatch(entry, "an array of numbers")),
+            })
+            .transpose()
+    }
+
+    pub fn vec3(&self, key: &str) -> Result<Option<Vec3>, SceneError> {
+        match (self.numbers(key)?, self.get(key)) {
+            (Some(values), Some(entry)) => match values[..] {
+                [x, y, z] => Ok(Some(Vec3::new(x, y, z))),
+                _ => Err(Self::mismatch(entry, "an array of 3 numbers")),
+            },
+            _ => Ok(Option::None),
+        }
+    }
+
+    pub fn required_vec3(&self, key: &str) -> Result<Vec3, SceneError> {
+        self.required(key, self.vec3(key)?)
+    }
+
+    // Bounds [min, max] of an interval
+    pub fn required_interval(&self, key: &str) -> Result<(f32, f32), SceneError> {
+        let entry = self.get(key);
+        match self.numbers(key)?.as_deref() {
+            Some(&[min, max]) if min <= max => Ok((min, max)),
+            Some(_) => Err(Self::mismatch(entry.unwrap(), "an interval [min, max]")),
+            None => self.required(key, Option::None),
+        }
+    }
+
+    pub fn vec3_list(&self, key: &str) -> Result<Option<Vec<Vec3>>, SceneError> {
+        self.get(key)
+            .map(|entry| match &entry.value {
+                Value::Array(values) => values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Array(v) => match v[..] {
+                            [Value::Number(x), Value::Number(y), Value::Number(z)] => {
+                                Ok(Vec3::new(x as f32, y as f32, z as f32))
+                            }
+                            _ => Err(Self::mismatch(entry, "an array of points")),
+                        },
+                        _ => Err(Self::mismatch(entry, "an array of points")),
+                    })
+                    .collect(),
+                _ => Err(Self::mismatch(entry, "an array of points")),
+            })
+            .transpose()
+    }
+}
+
+pub struct Document {
+    // The first table holds the keys defined before any table header
+    pub tables: Vec<Table>,
+}
+
+impl Document {
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+
+    pub fn tables_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Table> {
+        self.tables.iter().filter(move |table| table.name == name)
+    }
+}
+
+pub fn parse(content: &str) -> Result<Document, SceneError> {
+    let mut tables = vec![Table::new("", 0)];
+    let mut array_tables: Vec<String> = vec![];
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line = i + 1;
+        let text = strip_comment(raw_line).trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = text.strip_prefix("[[") {
+            let name = header
+                .strip_suffix("]]")
+                .map(str::trim)
+                .filter(|name| is_table_name(name))
+                .ok_or_else(|| SceneError::invalid(line, "invalid table header".to_string()))?;
+            if tables.iter().any(|t| t.name == name) && !array_tables.iter().any(|t| t == name) {
+                return Err(SceneError::invalid(
+                    line,
+                    format!("[[{}]] conflicts with table [{}]", name, name),
+                ));
+            }
+            array_tables.push(name.to_string());
+            tables.push(Table::new(name, line));
+        } else if let Some(header) = text.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| is_table_name(name))
+                .ok_or_else(|| SceneError::invalid(line, "invalid table header".to_string()))?;
+            if let Some(previous) = tables.iter().find(|t| t.name == name) {
+                return Err(SceneError::invalid(
+                    line,
+                    format!("table [{}] already defined line {}", name, previous.line),
+                ));
+            }
+            tables.push(Table::new(name, line));
+        } else {
+            let (key, value) =
+                parse_key_value(text).map_err(|msg| SceneError::invalid(line, msg))?;
+            let table = tables.last_mut().unwrap();
+            if let Some(previous) = table.get(&key) {
+                return Err(SceneError::invalid(
+                    line,
+                    format!("key '{}' already defined line {}", key, previous.line),
+                ));
+            }
+            table.entries.push(Entry { key, value, line });
+        }
+    }
+
+    Ok(Document { tables })
+}
+
+fn is_table_name(name: &str) -> bool {
+    !name.is_empty() && name.split('.').all(is_bare_key)
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Removes a trailing comment, ignoring '#' inside strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_key_value(text: &str) -> Result<(String, Value), String> {
+    let (key, value) = text
+        .split_once('=')
+        .ok_or_else(|| "expected 'key = value' or a [table] header".to_string())?;
+    let key = key.trim();
+    if !is_bare_key(key) {
+        return Err(format!("invalid key '{}'", key));
+    }
+
+    let mut chars = value.trim().chars().peekable();
+    let value = parse_value(&mut chars)?;
+    match chars.find(|c| !c.is_whitespace()) {
+        Some(c) => Err(format!("unexpected '{}' after value", c)),
+        None => Ok((key.to_string(), value)),
+    }
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn parse_value(chars: &mut Chars) -> Result<Value, String> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('"') => {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break Ok(Value::String(s)),
+                    Some('\\') => match chars.next() {
+                        Some('n') => s.push('\n'),
+                        Some('t') => s.push('\t'),
+                        Some(c @ '"') | Some(c @ '\\') => s.push(c),
+                        Some(c) => break Err(format!("invalid escape sequence '\\{}'", c)),
+                        None => break Err("unterminated string".to_string()),
+                    },
+                    Some(c) => s.push(c),
+                    None => break Err("unterminated string".to_string()),
+                }
+            }
+        }
+        Some('[') => {
+            chars.next();
+            let mut values = vec![];
+            loop {
+                skip_whitespace(chars);
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                    break Ok(Value::Array(values));
+                }
+                values.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some(']') => break Ok(Value::Array(values)),
+                    Some(c) => break Err(format!("expected ',' or ']' in array, found '{}'", c)),
+                    None => break Err("unterminated array".to_string()),
+                }
+            }
+        }
+        Some(_) => {
+            let mut token = String::new();
+            while let Some(c) = chars.peek() {
+                if c.is_whitespace() || *c == ',' || *c == ']' {
+                    break;
+                }
+                token.push(*c);
+                chars.next();
+            }
+            match token.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => token
+                    .replace('_', "")
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite())
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("invalid value '{}'", token)),
+            }
+        }
+        None => Err("missing value".to_string()),
+    }
+}
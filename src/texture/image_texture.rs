@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use crate::color::Color;
+use crate::geometry::Point3;
+use crate::import::ImportError;
+use crate::texture::Texture;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WrapMode {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl WrapMode {
+    fn apply(&self, i: isize, size: usize) -> usize {
+        let size = size as isize;
+        let wrapped = match self {
+            WrapMode::Repeat => i.rem_euclid(size),
+            WrapMode::Clamp => i.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                let period = i.rem_euclid(2 * size);
+                if period < size {
+                    period
+                } else {
+                    2 * size - 1 - period
+                }
+            }
+        };
+        wrapped as usize
+    }
+}
+
+// Bilinearly filtered texture, v = 0 is the bottom of the image
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>, wrap: WrapMode) -> Self {
+        assert_eq!(pixels.len(), width * height, "Invalid texture size!");
+
+        Self {
+            width,
+            height,
+            pixels,
+            wrap,
+        }
+    }
+
+    // Loads a PNG or JPEG image
+    pub fn load(path: &Path, wrap: WrapMode) -> Result<Self, ImportError> {
+        let image = image::open(path)
+            .map_err(|err| match err {
+                image::ImageError::IoError(err) => ImportError::Io(path.to_path_buf(), err),
+                err => ImportError::Decode(path.to_path_buf(), err.to_string()),
+            })?
+            .into_rgb8();
+
+        // Undo the gamma 2 applied to rendered images, so that a texture seen
+        // under a white light is rendered as is
+        let to_linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            c * c
+        };
+        let pixels = image
+            .pixels()
+            .map(|p| Color::new(to_linear(p[0]), to_linear(p[1]), to_linear(p[2])))
+            .collect();
+
+        Ok(Self::new(
+            image.width() as usize,
+            image.height() as usize,
+            pixels,
+            wrap,
+        ))
+    }
+
+    fn texel(&self, x: isize, y: isize) -> &Color {
+        let x = self.wrap.apply(x, self.width);
+        let y = self.wrap.apply(y, self.height);
+        &self.pixels[y * self.width + x]
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _p: &Point3) -> Color {
+        let x = u * self.width as f32 - 0.5;
+        let y = (1.0 - v) * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+
+        let top = (1.0 - fx) * self.texel(x0, y0) + fx * self.texel(x0 + 1, y0);
+        let bottom = (1.0 - fx) * self.texel(x0, y0 + 1) + fx * self.texel(x0 + 1, y0 + 1);
+        (1.0 - fy) * top + fy * bottom
+    }
+}
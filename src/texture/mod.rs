@@ -0,0 +1,57 @@
+//! Colors varying over surfaces, used as material albedos.
+
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::geometry::Point3;
+
+pub mod image_texture;
+pub mod perlin;
+
+pub trait Texture {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color;
+}
+
+pub struct SolidColor {
+    pub color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: &Color) -> Self {
+        Self { color: *color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        self.color
+    }
+}
+
+// Solid 3D checker pattern, scale is the size of a cell
+pub struct CheckerTexture {
+    pub odd: Arc<dyn Texture + Send + Sync>,
+    pub even: Arc<dyn Texture + Send + Sync>,
+    pub scale: f32,
+}
+
+impl CheckerTexture {
+    pub fn new(
+        odd: Arc<dyn Texture + Send + Sync>,
+        even: Arc<dyn Texture + Send + Sync>,
+        scale: f32,
+    ) -> Self {
+        Self { odd, even, scale }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        let cell = |x: f32| (x / self.scale).floor() as i64;
+        if (cell(p.x) + cell(p.y) + cell(p.z)).rem_euclid(2) == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
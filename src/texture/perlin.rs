@@ -0,0 +1,133 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::color::Color;
+use crate::geometry::{Point3, Vec3};
+use crate::texture::Texture;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise from "Ray Tracing: The Next Week"
+pub struct Perlin {
+    ranvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let ranvec = (0..POINT_COUNT)
+            .map(|_| Vec3::unit_vector(&Vec3::random_bounded(rng, -1.0, 1.0)))
+            .collect();
+
+        let mut generate_perm = || {
+            let mut perm: Vec<usize> = (0..POINT_COUNT).collect();
+            perm.shuffle(rng);
+            perm
+        };
+
+        Self {
+            ranvec,
+            perm_x: generate_perm(),
+            perm_y: generate_perm(),
+            perm_z: generate_perm(),
+        }
+    }
+
+    pub fn noise(&self, p: &Point3) -> f32 {
+        let (u, v, w) = (p.x - p.x.floor(), p.y - p.y.floor(), p.z - p.z.floor());
+        let (i, j, k) = (p.x.floor() as i64, p.y.floor() as i64, p.z.floor() as i64);
+        let wrap = |x: i64| (x & (POINT_COUNT as i64 - 1)) as usize;
+
+        let mut c = [[[Vec3::default(); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    *corner = self.ranvec[self.perm_x[wrap(i + di as i64)]
+                        ^ self.perm_y[wrap(j + dj as i64)]
+                        ^ self.perm_z[wrap(k + dk as i64)]];
+                }
+            }
+        }
+
+        // Trilinear interpolation with Hermite smoothing
+        let (uu, vv, ww) = (
+            u * u * (3.0 - 2.0 * u),
+            v * v * (3.0 - 2.0 * v),
+            w * w * (3.0 - 2.0 * w),
+        );
+        let mut accum = 0.0;
+        for (di, plane) in c.iter().enumerate() {
+            for (dj, row) in plane.iter().enumerate() {
+                for (dk, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (di as f32, dj as f32, dk as f32);
+                    let weight = Vec3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * Vec3::dot(corner, &weight);
+                }
+            }
+        }
+
+        accum
+    }
+
+    // Sum of noise octaves
+    pub fn turbulence(&self, p: &Point3, depth: u32) -> f32 {
+        let mut accum = 0.0;
+        let mut temp_p = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(&temp_p);
+            weight *= 0.5;
+            temp_p = 2.0 * temp_p;
+        }
+
+        accum.abs()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NoiseStyle {
+    Noise,
+    Turbulence,
+    // Veins following the z axis, perturbed by turbulence
+    Marble,
+}
+
+pub struct NoiseTexture {
+    noise: Perlin,
+    style: NoiseStyle,
+    color: Color,
+    scale: f32,
+    depth: u32,
+}
+
+impl NoiseTexture {
+    pub fn new(noise: Perlin, style: NoiseStyle, color: &Color, scale: f32, depth: u32) -> Self {
+        Self {
+            noise,
+            style,
+            color: *color,
+            scale,
+            depth,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Point3) -> Color {
+        let intensity = match self.style {
+            NoiseStyle::Noise => 0.5 * (1.0 + self.noise.noise(&(self.scale * p))),
+            NoiseStyle::Turbulence => self.noise.turbulence(&(self.scale * p), self.depth),
+            NoiseStyle::Marble => {
+                0.5 * (1.0 + (self.scale * p.z + 10.0 * self.noise.turbulence(p, self.depth)).sin())
+            }
+        };
+
+        intensity * self.color
+    }
+}